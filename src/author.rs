@@ -0,0 +1,263 @@
+use std::{io::Write as _, path::Path};
+
+use regex::Regex;
+use thirtyfour::{error::WebDriverResult, prelude::ElementQueryable as _, By, WebDriver};
+use url::Url;
+
+use crate::job_source::{parse_job_sources, JobSource};
+
+/// An interactive assistant for writing a new job source against a live or saved page.
+pub struct SourceAuthor {
+    /// The fields of the RON entry in the order they were set, with their RON values.
+    fields: Vec<(String, String)>,
+    /// The URL of the page.
+    url: Url,
+    /// The HTML of the page to parse jobs from.
+    html: String,
+}
+
+impl SourceAuthor {
+    /// The fields that are not wrapped in `Some(..)` by `set`.
+    const REQUIRED_FIELDS: [&str; 4] = ["name", "url", "next_job_re", "job_title_re"];
+    const HELP: &str = "\
+set <field> <value>  Set a field to a string (wrapped in Some(..) if optional)
+raw <field> <ron>    Set a field to a RON value
+unset <field>        Remove a field
+reload               Reload the HTML of the page open in the browser
+find <regex>         Show the matches of a regex in the HTML
+select <css>         Show the elements matching a CSS selector in the HTML
+jobs                 Show every job parsed from the HTML
+show                 Show the RON entry
+save <path>          Write the RON entry to a file
+quit                 Show the RON entry and quit";
+
+    /// Starts from a URL or a saved HTML file.
+    pub fn new(url: &Url, html: String) -> Self {
+        let mut author = Self {
+            fields: vec![],
+            url: url.clone(),
+            html,
+        };
+        author.set("name", url.host_str().unwrap_or("New"));
+        author.set("url", url.as_str());
+        author
+    }
+
+    /// Opens a URL in the browser, or loads a saved HTML file if `driver` is `None`.
+    pub async fn open(target: &str, driver: Option<&WebDriver>) -> WebDriverResult<Self> {
+        let (url, html) = match driver {
+            Some(driver) => {
+                driver.goto(target).await?;
+                (driver.current_url().await?, page_html(driver).await?)
+            }
+            None => {
+                let path = std::fs::canonicalize(target)?;
+                let url = Url::from_file_path(&path).unwrap();
+                (url, std::fs::read_to_string(path)?)
+            }
+        };
+        Ok(Self::new(&url, html))
+    }
+
+    /// Proposes a job source from the repeated job cards in the page, if there are any.
+    pub fn infer(&self) -> Option<Self> {
+        crate::infer::infer_job_source(&self.url, &self.html)
+    }
+
+    /// Reads commands from stdin until `quit` or the end of input.
+    pub async fn run(&mut self, driver: Option<&WebDriver>) -> WebDriverResult<()> {
+        println!("{}\n", Self::HELP);
+        self.preview(10);
+
+        // Read stdin on its own thread, so that waiting for a command can be interrupted.
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        std::thread::spawn(move || {
+            for line in std::io::stdin().lines() {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        loop {
+            print!("> ");
+            std::io::stdout().flush()?;
+            let Some(line) = rx.recv().await else {
+                break;
+            };
+
+            let line = line?;
+            let line = line.trim();
+            let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+            let (field, value) = args.split_once(' ').unwrap_or((args, ""));
+            match command {
+                "" => {}
+                "set" => {
+                    self.set(field, value);
+                    self.preview(10);
+                }
+                "raw" => {
+                    self.set_raw(field, value);
+                    self.preview(10);
+                }
+                "unset" => {
+                    self.fields.retain(|(x, _)| x != field);
+                    self.preview(10);
+                }
+                "reload" => {
+                    let Some(driver) = driver else {
+                        println!("There's no browser to reload from");
+                        continue;
+                    };
+                    self.html = match self.parse() {
+                        Ok(job_source) => job_source.root(driver).await?.outer_html().await?,
+                        Err(_) => page_html(driver).await?,
+                    };
+                    self.preview(10);
+                }
+                "find" => self.find(args),
+                "select" => self.select(args),
+                "jobs" => self.preview(usize::MAX),
+                "show" => println!("{}", self.to_ron()),
+                "save" => match std::fs::write(Path::new(args), self.to_ron()) {
+                    Ok(()) => println!("Saved to {}", args),
+                    Err(e) => println!("Couldn't save to {}: {}", args, e),
+                },
+                "quit" => break,
+                _ => println!("{}", Self::HELP),
+            }
+        }
+
+        println!("{}", self.to_ron());
+        Ok(())
+    }
+
+    /// Sets a field to a string, wrapped in `Some(..)` if the field is optional.
+    pub fn set(&mut self, field: &str, value: &str) {
+        let value = ron_str(value);
+        if Self::REQUIRED_FIELDS.contains(&field) {
+            self.set_raw(field, &value);
+        } else {
+            self.set_raw(field, &format!("Some({})", value));
+        }
+    }
+
+    /// Sets a field to a RON value.
+    pub fn set_raw(&mut self, field: &str, value: &str) {
+        match self.fields.iter_mut().find(|(x, _)| x == field) {
+            Some((_, x)) => *x = value.to_string(),
+            None => self.fields.push((field.to_string(), value.to_string())),
+        }
+    }
+
+    /// Returns the job source as a RON entry for `job_sources.ron`.
+    pub fn to_ron(&self) -> String {
+        let mut ron = "(\n".to_string();
+        for (field, value) in &self.fields {
+            ron += &format!("    {}: {},\n", field, value);
+        }
+        ron += "),";
+        ron
+    }
+
+    pub(crate) fn parse(&self) -> Result<JobSource, String> {
+        let (mut job_sources, problems) = parse_job_sources(&format!("[{}]", self.to_ron()));
+        match job_sources.pop() {
+            Some(job_source) => Ok(job_source),
+            None => Err(problems
+                .into_iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join("\n")),
+        }
+    }
+
+    /// Shows the problems with the job source, or the first few jobs it parses from the HTML.
+    pub fn preview(&self, max_jobs: usize) {
+        let job_source = match self.parse() {
+            Ok(x) => x,
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        };
+        for problem in job_source.validate() {
+            println!("{}", problem);
+        }
+
+        let jobs = job_source.parse_page(&self.url, &self.html);
+        let mut jobs = jobs.iter().collect::<Vec<_>>();
+        jobs.sort_by_key(|(_, job)| &job.title);
+        println!("Found {} jobs", jobs.len());
+        for (id, job) in jobs.into_iter().take(max_jobs) {
+            println!("[{}] {} ({}) {{{}}}", job.company, job, job.url, id);
+        }
+    }
+
+    fn find(&self, re: &str) {
+        let re = match Regex::new(re) {
+            Ok(x) => x,
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        };
+        let captures = re.captures_iter(&self.html).collect::<Vec<_>>();
+        println!("Found {} matches", captures.len());
+        for captures in captures.iter().take(5) {
+            for (i, group) in captures.iter().enumerate() {
+                let group = group.map_or("", |x| x.as_str());
+                println!("{:>4}: {}", i, truncated(group, 200));
+            }
+        }
+    }
+
+    fn select(&self, css: &str) {
+        let selector = match scraper::Selector::parse(css) {
+            Ok(x) => x,
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        };
+        let html = scraper::Html::parse_document(&self.html);
+        let elements = html.select(&selector).collect::<Vec<_>>();
+        println!("Found {} elements", elements.len());
+        for element in elements.iter().take(5) {
+            println!("{}", truncated(&element.html(), 200));
+        }
+    }
+}
+
+async fn page_html(driver: &WebDriver) -> WebDriverResult<String> {
+    // A job source with a frame sub-DOM may have left the driver inside the frame.
+    driver.enter_default_frame().await?;
+    driver
+        .query(By::Css("*"))
+        .nowait()
+        .first()
+        .await?
+        .outer_html()
+        .await
+}
+
+/// Quotes a string for RON, using a raw string if it would need escapes.
+fn ron_str(s: &str) -> String {
+    if !s.contains(['"', '\\']) {
+        return format!("\"{}\"", s);
+    }
+
+    let mut hashes = "#".to_string();
+    while s.contains(&format!("\"{}", hashes)) {
+        hashes += "#";
+    }
+    format!("r{}\"{}\"{}", hashes, s, hashes)
+}
+
+fn truncated(s: &str, max_chars: usize) -> String {
+    let s = s.split_whitespace().collect::<Vec<_>>().join(" ");
+    match s.char_indices().nth(max_chars) {
+        Some((i, _)) => format!("{}...", &s[..i]),
+        None => s,
+    }
+}
@@ -1,35 +1,80 @@
 use std::{
-    collections::HashMap,
-    process::{Child, Command, Stdio},
+    collections::{BTreeMap, HashMap},
+    net::TcpStream,
+    path::{Path, PathBuf},
+    process::Child,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use chrono::Utc;
+use chrono::{DateTime, Local, Utc};
 use colored::{Color, Colorize as _};
 use thirtyfour::{
     common::config::WebDriverConfig, extensions::query::ElementPollerWithTimeout, prelude::*,
     AlertBehaviour,
 };
 use tiny_bail::prelude::*;
+use tokio::runtime::RuntimeFlavor;
 
-use crate::{job::Job, job_source::JobSource};
+use crate::{
+    browser::{Browser, BrowserConfig},
+    filter::{JobFilter, JobSort},
+    health::{Anomaly, HealthConfig, SourceHealth, SourceRun},
+    job::Job,
+    job_source::{parse_job_sources, JobSource, SourceProblem, SourceSelection},
+    journal::RunJournal,
+    notify::{Digest, NotifyConfig},
+    profile::{JobState, Profile},
+    summary::{ScrapeSummary, SourceSummary},
+    webhook::NotifyEvent,
+};
 
 #[derive(Default)]
 pub struct Bot {
+    /// The directory containing the job database, job sources and profiles.
+    pub data_dir: PathBuf,
     server: Option<Child>,
     pub driver: Option<WebDriver>,
+    /// Whether the browser was started headless, to restart it the same way.
+    headless: bool,
     pub job_sources: Vec<JobSource>,
     pub jobs: HashMap<String, Job>,
+    /// The jobs removed from the job database recently, by job ID.
+    pub removed_jobs: HashMap<String, Job>,
+    pub profile: Profile,
+    /// The recent run history of each job source by name.
+    pub health: BTreeMap<String, SourceHealth>,
+    pub health_config: HealthConfig,
+    pub browser: BrowserConfig,
+    pub notify: NotifyConfig,
+    /// The events to post to webhooks.
+    events: Vec<NotifyEvent>,
 }
 
 impl Bot {
-    const JOBS_FILE_PATH: &str = "data/jobs.ron";
-    const JOBS_BACKUP_FILE_PATH: &str = "data/jobs.backup.ron";
-    const JOB_SOURCES_FILE_PATH: &str = "data/job_sources.ron";
+    pub const DEFAULT_DATA_DIR: &str = "data";
+    const JOBS_FILE_NAME: &str = "jobs.ron";
+    const JOBS_BACKUP_FILE_NAME: &str = "jobs.backup.ron";
+    const JOB_SOURCES_FILE_NAME: &str = "job_sources.ron";
+    const HEALTH_FILE_NAME: &str = "health.ron";
+    const JOURNAL_FILE_NAME: &str = "scrape_journal.ron";
+    const NOTIFY_FILE_NAME: &str = "notify.ron";
+    const PROFILES_DIR_NAME: &str = "profiles";
+    const REMOVED_JOBS_FILE_NAME: &str = "removed_jobs.ron";
+    const REMOVED_JOBS_BACKUP_FILE_NAME: &str = "removed_jobs.backup.ron";
+    /// The number of days to keep removed jobs around, counting from when they went missing.
+    const REMOVED_JOBS_DAYS: i64 = 30;
+    const SUMMARIES_DIR_NAME: &str = "summaries";
+    const WEBDRIVER_LOG_FILE_NAME: &str = "webdriver.log";
 
     pub fn new() -> Self {
-        Self::default()
+        Self::with_data_dir(Self::DEFAULT_DATA_DIR)
+    }
+
+    pub fn with_data_dir(data_dir: impl Into<PathBuf>) -> Self {
+        let mut bot = Self::default();
+        bot.data_dir = data_dir.into();
+        bot
     }
 
     pub async fn init(&mut self) -> WebDriverResult<()> {
@@ -43,51 +88,153 @@ impl Bot {
     async fn init_helper(&mut self, headless: bool) -> WebDriverResult<()> {
         assert!(self.server.is_none() && self.driver.is_none());
 
-        // Spawn WebDriver server as a child process.
-        let server = Command::new("geckodriver")
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .spawn()?;
-        std::thread::sleep(Duration::from_millis(100));
+        self.headless = headless;
 
-        // Connect to WebDriver server.
-        let mut caps = DesiredCapabilities::firefox();
-        if headless {
-            caps.set_headless()?;
+        // Spawn WebDriver server as a child process, unless connecting to a remote one.
+        if let Some(mut command) = self.browser.server_command() {
+            // Don't connect to a leftover server from a run that didn't clean up.
+            if TcpStream::connect(("localhost", self.browser.port)).is_ok() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::AddrInUse,
+                    format!("port {} is already in use", self.browser.port),
+                )
+                .into());
+            }
+
+            // Keep the server's output for debugging.
+            std::fs::create_dir_all(&self.data_dir)?;
+            let log_file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.data_dir.join(Self::WEBDRIVER_LOG_FILE_NAME))?;
+            let server = command
+                .stdout(log_file.try_clone()?)
+                .stderr(log_file)
+                .spawn()?;
+            self.server = Some(server);
+            self.wait_for_server().await?;
         }
-        caps.set_unexpected_alert_behaviour(AlertBehaviour::Dismiss)?;
+
+        // Connect to WebDriver server.
         let config = WebDriverConfig::builder()
             .poller(Arc::new(ElementPollerWithTimeout::new(
                 Duration::from_secs(8),
                 Duration::from_millis(100),
             )))
             .build()?;
-        let driver = WebDriver::new_with_config("http://localhost:4444", caps, config).await?;
-
-        self.server = Some(server);
+        let server_url = self.browser.server_url();
+        let driver = match self.browser.browser {
+            Browser::Firefox => {
+                let mut caps = DesiredCapabilities::firefox();
+                if headless {
+                    caps.set_headless()?;
+                }
+                caps.set_unexpected_alert_behaviour(AlertBehaviour::Dismiss)?;
+                WebDriver::new_with_config(&server_url, caps, config).await?
+            }
+            Browser::Chromium => {
+                let mut caps = DesiredCapabilities::chrome();
+                if headless {
+                    caps.set_headless()?;
+                }
+                caps.set_unexpected_alert_behaviour(AlertBehaviour::Dismiss)?;
+                WebDriver::new_with_config(&server_url, caps, config).await?
+            }
+        };
         self.driver = Some(driver);
 
         Ok(())
     }
 
-    pub async fn quit(self) -> WebDriverResult<()> {
-        self.driver.unwrap().quit().await?;
-        self.server.unwrap().kill()?;
-        Ok(())
+    /// Waits until the spawned WebDriver server accepts connections.
+    async fn wait_for_server(&mut self) -> WebDriverResult<()> {
+        let server = self.server.as_mut().unwrap();
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(10) {
+            if let Some(status) = server.try_wait()? {
+                return Err(std::io::Error::other(format!(
+                    "WebDriver server exited early with {}",
+                    status,
+                ))
+                .into());
+            }
+            if TcpStream::connect(("localhost", self.browser.port)).is_ok() {
+                return Ok(());
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        Err(std::io::Error::new(
+            std::io::ErrorKind::TimedOut,
+            "WebDriver server didn't start listening",
+        )
+        .into())
+    }
+
+    pub async fn quit(mut self) -> WebDriverResult<()> {
+        self.shutdown().await
+    }
+
+    /// Closes the browser session and stops the WebDriver server, if they're running.
+    async fn shutdown(&mut self) -> WebDriverResult<()> {
+        let result = match self.driver.take() {
+            Some(driver) => driver.quit().await,
+            None => Ok(()),
+        };
+        // There's no server to kill when connected to a remote one.
+        if let Some(mut server) = self.server.take() {
+            server.kill()?;
+            server.wait()?;
+        }
+        result
+    }
+
+    /// Restarts the browser if its session or the WebDriver server crashed.
+    ///
+    /// Returns true if the browser was restarted.
+    async fn restart_if_crashed(&mut self) -> WebDriverResult<bool> {
+        let server_exited = match &mut self.server {
+            Some(server) => server.try_wait()?.is_some(),
+            None => false,
+        };
+        let session_alive = match &self.driver {
+            Some(driver) => driver.title().await.is_ok(),
+            None => false,
+        };
+        if session_alive && !server_exited {
+            return Ok(false);
+        }
+
+        log::warn!("Restarting the browser after a crash");
+        if let Err(e) = self.shutdown().await {
+            log::debug!("Couldn't close the crashed browser: {}", e);
+        }
+        self.init_helper(self.headless).await?;
+        Ok(true)
     }
 
     pub fn load(&mut self) {
         self.load_jobs();
         self.load_job_sources();
+        self.load_health();
+        self.load_notify();
     }
 
     pub fn save(&mut self) {
         self.save_jobs();
+        self.save_health();
     }
 
     pub fn load_jobs(&mut self) {
-        let jobs_str = r!(std::fs::read_to_string(Self::JOBS_FILE_PATH));
+        let jobs_str = r!(std::fs::read_to_string(
+            self.data_dir.join(Self::JOBS_FILE_NAME)
+        ));
         self.jobs = r!(ron::from_str(&jobs_str));
+
+        let removed_jobs_str = rq!(std::fs::read_to_string(
+            self.data_dir.join(Self::REMOVED_JOBS_FILE_NAME)
+        ));
+        self.removed_jobs = r!(ron::from_str(&removed_jobs_str));
     }
 
     // Re-parse jobs from their titles. Useful when parsing logic changes.
@@ -98,27 +245,220 @@ impl Bot {
     }
 
     pub fn load_job_sources(&mut self) {
-        let job_sources_str = r!(std::fs::read_to_string(Self::JOB_SOURCES_FILE_PATH));
-        self.job_sources = ron::from_str(&job_sources_str).unwrap();
+        let job_sources_str = r!(std::fs::read_to_string(
+            self.data_dir.join(Self::JOB_SOURCES_FILE_NAME)
+        ));
+        let (job_sources, problems) = parse_job_sources(&job_sources_str);
+        for problem in problems {
+            log::warn!("Skipping job source: {}", problem);
+        }
+        self.job_sources = job_sources;
+    }
+
+    pub fn load_health(&mut self) {
+        // There's no run history before the first scrape.
+        let health_str = rq!(std::fs::read_to_string(
+            self.data_dir.join(Self::HEALTH_FILE_NAME)
+        ));
+        self.health = r!(ron::from_str(&health_str));
+    }
+
+    pub fn save_health(&self) {
+        let health_str = r!(ron::ser::to_string_pretty(&self.health, Default::default(),));
+        r!(write_atomic(
+            &self.data_dir.join(Self::HEALTH_FILE_NAME),
+            health_str,
+        ));
+    }
+
+    pub fn load_notify(&mut self) {
+        // Notifications are off unless configured.
+        let notify_str = rq!(std::fs::read_to_string(
+            self.data_dir.join(Self::NOTIFY_FILE_NAME)
+        ));
+        self.notify = r!(ron::from_str(&notify_str));
+    }
+
+    /// Sends notifications about new jobs by their IDs, if configured.
+    pub async fn notify_new_jobs<'a>(&self, ids: impl IntoIterator<Item = &'a String>) {
+        let jobs = ids
+            .into_iter()
+            .filter_map(|id| self.jobs.get_key_value(id))
+            .map(|(id, job)| (self.profile.score(id, job), job))
+            .collect::<Vec<_>>();
+
+        if let Some(email) = &self.notify.email {
+            let digest = Digest::new(jobs.iter().copied(), email.min_score);
+            if let Err(e) = email.send(&digest).await {
+                log::error!("Couldn't send email: {}", e);
+            }
+        }
+    }
+
+    /// Posts the events so far to the webhooks, if configured.
+    pub async fn post_events(&mut self) {
+        let events = std::mem::take(&mut self.events);
+        if events.is_empty() || self.notify.webhooks.is_empty() {
+            return;
+        }
+
+        let client = reqwest::Client::new();
+        for webhook in &mut self.notify.webhooks {
+            webhook.post(&client, &events).await;
+        }
+    }
+
+    /// Posts new job events for jobs by their IDs to the webhooks, if configured.
+    pub async fn post_new_jobs<'a>(&mut self, ids: impl IntoIterator<Item = &'a String>) {
+        for id in ids {
+            let job = c!(self.jobs.get(id));
+            self.events.push(NotifyEvent::NewJob {
+                score: self.profile.score(id, job),
+                job: job.clone(),
+            });
+        }
+        self.post_events().await;
+    }
+
+    /// Checks the job sources file for every problem that can be found without scraping.
+    pub fn validate_job_sources(&self) -> Vec<SourceProblem> {
+        let path = self.data_dir.join(Self::JOB_SOURCES_FILE_NAME);
+        let job_sources_str = match std::fs::read_to_string(&path) {
+            Ok(x) => x,
+            Err(e) => {
+                return vec![SourceProblem {
+                    source: path.display().to_string(),
+                    field: None,
+                    message: e.to_string(),
+                }]
+            }
+        };
+
+        let (job_sources, mut problems) = parse_job_sources(&job_sources_str);
+        for (i, job_source) in job_sources.iter().enumerate() {
+            problems.extend(job_source.validate());
+            if job_sources[..i].iter().any(|x| x.name == job_source.name) {
+                problems.push(SourceProblem {
+                    source: job_source.name.clone(),
+                    field: Some("name".to_string()),
+                    message: "is not unique".to_string(),
+                });
+            }
+        }
+
+        problems
+    }
+
+    /// Loads a profile, or starts a new one with the defaults if it hasn't been saved yet.
+    ///
+    /// Fails if the profile can't be read or parsed, so that saving doesn't overwrite it.
+    pub fn load_profile(&mut self, name: &str) -> std::io::Result<()> {
+        let path = self.profile_file_path(name);
+        let profile_str = match std::fs::read_to_string(&path) {
+            Ok(profile_str) => profile_str,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                self.profile = Profile::new(name);
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+        self.profile = ron::from_str(&profile_str).map_err(|e| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("couldn't parse {}: {}", path.display(), e),
+            )
+        })?;
+        self.profile.name = name.to_string();
+        Ok(())
+    }
+
+    pub fn save_profile(&self) {
+        let path = self.profile_file_path(&self.profile.name);
+        r!(std::fs::create_dir_all(
+            self.data_dir.join(Self::PROFILES_DIR_NAME)
+        ));
+        // A profile that hasn't been saved yet has nothing to back up.
+        if path.exists() {
+            r!(std::fs::copy(&path, path.with_extension("backup.ron")));
+        }
+        let profile_str = r!(ron::ser::to_string_pretty(
+            &self.profile,
+            Default::default(),
+        ));
+        r!(write_atomic(&path, profile_str));
+    }
+
+    fn profile_file_path(&self, name: &str) -> PathBuf {
+        self.data_dir
+            .join(Self::PROFILES_DIR_NAME)
+            .join(format!("{}.ron", name))
     }
 
     pub fn save_jobs(&self) {
+        self.backup_jobs();
+        self.write_jobs();
+    }
+
+    /// Copies the saved job database and removed jobs to their backup files.
+    fn backup_jobs(&self) {
         r!(std::fs::copy(
-            Self::JOBS_FILE_PATH,
-            Self::JOBS_BACKUP_FILE_PATH,
+            self.data_dir.join(Self::JOBS_FILE_NAME),
+            self.data_dir.join(Self::JOBS_BACKUP_FILE_NAME),
+        ));
+        // The removed jobs file doesn't exist until the first scrape that saves it.
+        rq!(std::fs::copy(
+            self.data_dir.join(Self::REMOVED_JOBS_FILE_NAME),
+            self.data_dir.join(Self::REMOVED_JOBS_BACKUP_FILE_NAME),
         ));
+    }
+
+    fn write_jobs(&self) {
         let jobs_str = r!(ron::to_string(&self.jobs));
-        r!(std::fs::write(Self::JOBS_FILE_PATH, jobs_str));
+        r!(write_atomic(
+            &self.data_dir.join(Self::JOBS_FILE_NAME),
+            jobs_str,
+        ));
+
+        let removed_jobs_str = r!(ron::to_string(&self.removed_jobs));
+        r!(write_atomic(
+            &self.data_dir.join(Self::REMOVED_JOBS_FILE_NAME),
+            removed_jobs_str,
+        ));
+    }
+
+    /// Loads the journal of an interrupted scrape run, if there is one.
+    fn load_journal(&self) -> Option<RunJournal> {
+        let journal_str =
+            std::fs::read_to_string(self.data_dir.join(Self::JOURNAL_FILE_NAME)).ok()?;
+        match ron::from_str(&journal_str) {
+            Ok(journal) => Some(journal),
+            Err(e) => {
+                log::warn!("Ignoring a broken scrape journal: {}", e);
+                None
+            }
+        }
+    }
+
+    fn save_journal(&self, journal: &RunJournal) {
+        let journal_str = r!(ron::ser::to_string_pretty(journal, Default::default()));
+        r!(write_atomic(
+            &self.data_dir.join(Self::JOURNAL_FILE_NAME),
+            journal_str,
+        ));
     }
 
-    pub fn list_jobs(&self) {
+    pub fn list_jobs(&self, filter: &JobFilter) {
         let now = Utc::now();
-        for (_, job) in sorted(&self.jobs) {
-            cq!(job.missing_since.is_none());
+        for (id, job) in self.filtered_jobs(filter) {
             let age = (now - job.first_seen).num_days();
+            let state = self.profile.jobs.get(id);
+            let status = state
+                .and_then(|x| x.status)
+                .map(|x| x.to_string())
+                .unwrap_or_default();
             // Ugly code makes pretty colors.
             println!(
-                "{} {} {} {}",
+                "{} {} {:12} {} {}",
                 format!("{:>2} days ago", age.to_string().bold()).color(if age == 0 {
                     Color::Cyan
                 } else if age < 7 {
@@ -130,12 +470,17 @@ impl Bot {
                 } else {
                     Color::Red
                 }),
-                format!("{:12}", job.company.chars().take(12).collect::<String>()),
+                format!("{:12}", status).color(if state.is_some_and(|x| x.is_follow_up_due()) {
+                    Color::Yellow
+                } else {
+                    Color::Blue
+                }),
+                job.company.chars().take(12).collect::<String>(),
                 format!(
                     "{:64}",
                     job.to_string().chars().take(64).collect::<String>(),
                 )
-                .color(if job.score() > 0 {
+                .color(if self.profile.score(id, job) > 0 {
                     Color::Green
                 } else {
                     Color::Red
@@ -145,32 +490,266 @@ impl Bot {
         }
     }
 
-    pub async fn update_jobs(&mut self) {
+    /// Returns the jobs that match a filter, sorted by the profile's preferences.
+    pub fn filtered_jobs(&self, filter: &JobFilter) -> Vec<(&String, &Job)> {
+        let removed = self
+            .profile
+            .jobs
+            .iter()
+            .filter(|(id, _)| filter.includes_removed() && !self.jobs.contains_key(*id))
+            .filter_map(|(id, state)| Some((id, state.job.as_ref()?)));
+        let jobs = self
+            .jobs
+            .iter()
+            .chain(removed)
+            .filter(|(id, job)| filter.matches(id, job, &self.profile));
+        let mut jobs = sorted(jobs, &self.profile, filter.sort);
+        if filter.reverse {
+            jobs.reverse();
+        }
+        if let Some(limit) = filter.limit {
+            jobs.drain(..jobs.len().saturating_sub(limit));
+        }
+        jobs
+    }
+
+    /// Finds the ID of a job from its ID or URL.
+    pub fn find_job_id(&self, id_or_url: &str) -> Option<String> {
+        if self.jobs.contains_key(id_or_url)
+            || self.removed_jobs.contains_key(id_or_url)
+            || self.profile.jobs.contains_key(id_or_url)
+        {
+            return Some(id_or_url.to_string());
+        }
+
+        let removed = self
+            .profile
+            .jobs
+            .iter()
+            .filter_map(|(id, state)| Some((id, state.job.as_ref()?)));
+        self.jobs
+            .iter()
+            .chain(&self.removed_jobs)
+            .chain(removed)
+            .find(|(_, job)| job.url.as_str() == id_or_url)
+            .map(|(id, _)| id.clone())
+    }
+
+    /// Returns a job by its ID, even if it's been removed from the job database.
+    pub fn get_job(&self, id: &str) -> Option<&Job> {
+        self.jobs
+            .get(id)
+            .or_else(|| self.removed_jobs.get(id))
+            .or_else(|| self.profile.jobs.get(id)?.job.as_ref())
+    }
+
+    /// Returns the profile's state for a job by its ID or URL, starting to track it if needed.
+    pub fn track_job(&mut self, id_or_url: &str) -> Option<&mut JobState> {
+        let id = self.find_job_id(id_or_url)?;
+        let state = self.profile.jobs.entry(id.clone()).or_default();
+        if let Some(job) = self.jobs.get(&id).or(self.removed_jobs.get(&id)) {
+            state.job = Some(job.clone());
+        }
+        Some(state)
+    }
+
+    /// Scrapes the selected job sources. Jobs from other job sources are left untouched.
+    ///
+    /// Progress is saved after each job source. If `resume` is true, the job sources that
+    /// finished in the last interrupted run are skipped.
+    ///
+    /// Returns a summary of what changed, which is also saved to the data directory.
+    pub async fn update_jobs(
+        &mut self,
+        selection: &SourceSelection,
+        resume: bool,
+    ) -> ScrapeSummary {
+        let started = Utc::now();
+        let start = Instant::now();
+        let names = self
+            .job_sources
+            .iter()
+            .map(|x| x.name.as_str())
+            .collect::<Vec<_>>();
+        for pattern in selection.unmatched(&names) {
+            log::warn!("No job source found matching: {}", pattern);
+        }
+
+        let journal = if resume { self.load_journal() } else { None };
+        let mut journal = match journal {
+            Some(journal) => {
+                log::info!(
+                    "Resuming the run started at {} ({} job sources finished)",
+                    journal
+                        .started
+                        .with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M"),
+                    journal.finished.len(),
+                );
+                journal
+            }
+            None => {
+                if resume {
+                    log::info!("No interrupted run to resume");
+                }
+                // Back up the jobs from before the run, since they're saved as it goes.
+                self.backup_jobs();
+                RunJournal::default()
+            }
+        };
+
+        let mut sources = vec![];
+        let mut complete = true;
         for i in 0..self.job_sources.len() {
-            cq!(self.update_job_source(i).await);
+            let name = self.job_sources[i].name.clone();
+            cq!(selection.matches(&name));
+            if journal.finished.contains(&name) {
+                log::debug!("[{}] Already scraped in this run", name);
+                continue;
+            }
+            let summary = match self.update_job_source(i).await {
+                Ok(summary) => summary,
+                Err(e) => {
+                    log::error!("Couldn't restart the browser: {}", e);
+                    complete = false;
+                    break;
+                }
+            };
+
+            // Save the progress so far.
+            self.write_jobs();
+            self.save_health();
+            journal.finished.push(name);
+            journal.new_ids.extend(summary.new_ids.iter().cloned());
+            self.save_journal(&journal);
+            self.post_events().await;
+            sources.push(summary);
+        }
+
+        // There's nothing to resume once the run is complete.
+        if complete {
+            let _ = std::fs::remove_file(self.data_dir.join(Self::JOURNAL_FILE_NAME));
         }
+
+        // Summarize the run, with the most relevant new jobs.
+        for source in &sources {
+            if let Some(anomaly) = &source.anomaly {
+                log::warn!("[{}] Possibly broken: {}", source.name, anomaly);
+            }
+        }
+        let new_jobs = journal
+            .new_ids
+            .iter()
+            .filter_map(|id| self.jobs.get_key_value(id));
+        let top_new_jobs = sorted(new_jobs, &self.profile, JobSort::Score)
+            .into_iter()
+            .rev()
+            .take(ScrapeSummary::MAX_TOP_NEW_JOBS)
+            .map(|(id, job)| (self.profile.score(id, job), job.clone()))
+            .collect();
+        let summary = ScrapeSummary {
+            started,
+            seconds: start.elapsed().as_secs_f64(),
+            sources,
+            top_new_jobs,
+            new_ids: journal.new_ids,
+        };
+        self.save_summary(&summary);
+        summary
+    }
+
+    /// Saves a scrape summary to the summaries directory, named by when the run started.
+    fn save_summary(&self, summary: &ScrapeSummary) {
+        let dir = self.data_dir.join(Self::SUMMARIES_DIR_NAME);
+        r!(std::fs::create_dir_all(&dir));
+        let summary_str = r!(ron::ser::to_string_pretty(summary, Default::default()));
+        let file_name = format!(
+            "{}.ron",
+            summary
+                .started
+                .with_timezone(&Local)
+                .format("%Y-%m-%d_%H-%M-%S"),
+        );
+        r!(std::fs::write(dir.join(file_name), summary_str));
     }
 
-    pub async fn update_job_source(&mut self, idx: usize) -> WebDriverResult<()> {
+    /// Scrapes a job source and records the run in its history, returning what changed.
+    ///
+    /// If the browser crashed, the job source is scraped again with a fresh browser, and the two
+    /// attempts are recorded as one run. Returns an error if the browser couldn't be restarted.
+    ///
+    /// If the job source looks broken, the jobs that weren't found are not marked missing.
+    pub async fn update_job_source(&mut self, idx: usize) -> WebDriverResult<SourceSummary> {
         let now = Utc::now();
+
+        // Scrape the job source, trying again with a fresh browser if the browser crashed.
+        let start = Instant::now();
+        let mut result = self.job_sources[idx]
+            .scrape(self.driver.as_ref().unwrap())
+            .await;
+        if result.is_err() && self.restart_if_crashed().await? {
+            result = self.job_sources[idx]
+                .scrape(self.driver.as_ref().unwrap())
+                .await;
+        }
+
+        // Record the run.
         let job_source = &self.job_sources[idx];
-        let mut jobs = job_source.scrape(self.driver.as_ref().unwrap()).await?;
+        let mut summary = SourceSummary {
+            name: job_source.name.clone(),
+            ..Default::default()
+        };
+        let open_jobs = self
+            .jobs
+            .values()
+            .filter(|x| x.source == job_source.name && x.missing_since.is_none())
+            .count();
+        summary.seconds = start.elapsed().as_secs_f64();
+        summary.jobs = result.as_ref().map_or(0, |(jobs, _)| jobs.len());
+        summary.error = result.as_ref().err().map(|e| e.to_string());
+        let health = self.health.entry(job_source.name.clone()).or_default();
+        let was_broken = health.check(open_jobs, &self.health_config).is_some();
+        health.push(SourceRun {
+            time: now,
+            jobs: summary.jobs,
+            pages: result.as_ref().map_or(0, |&(_, pages)| pages),
+            seconds: summary.seconds,
+            error: summary.error.clone(),
+        });
+        summary.anomaly = health.check(open_jobs, &self.health_config);
+        // Only tell the chat when the job source breaks, not on every run while it's broken.
+        if let Some(anomaly) = summary.anomaly.as_ref().filter(|_| !was_broken) {
+            self.events.push(NotifyEvent::SourceBroken {
+                source: job_source.name.clone(),
+                message: anomaly.to_string(),
+            });
+        }
+        let mut jobs = match result {
+            Ok((jobs, _)) => jobs,
+            Err(e) => {
+                log::warn!("[{}] Failed to scrape: {}", job_source.name, e);
+                return Ok(summary);
+            }
+        };
+        let trusted = !summary.anomaly.as_ref().is_some_and(Anomaly::is_untrusted);
 
         // Set `missing_since` for old jobs that are now missing.
         for (id, old) in &mut self.jobs {
-            cq!(old.source == job_source.name
+            cq!(trusted
+                && old.source == job_source.name
                 && !jobs.contains_key(id)
                 && old.missing_since.is_none());
 
             log::info!(
                 "{}[{}] Missing after {} days: {} ({})",
-                old.prefix(),
+                old.prefix(&self.profile.score),
                 old.company,
                 (now - old.first_seen).num_days(),
                 old,
                 old.url,
             );
             old.missing_since = Some(now);
+            summary.missing += 1;
         }
 
         // Set `first_seen` for new jobs that have already been seen.
@@ -180,55 +759,170 @@ impl Bot {
                 if let Some(missing_since) = old.missing_since {
                     log::info!(
                         "{}[{}] Recovered after {} days: {} ({})",
-                        old.prefix(),
+                        old.prefix(&self.profile.score),
                         old.company,
                         (now - missing_since).num_days(),
                         old,
                         old.url,
                     );
+                    summary.recovered += 1;
+                    self.events.push(NotifyEvent::RecoveredJob {
+                        score: self.profile.score(id, old),
+                        job: new.clone(),
+                    });
                 }
             } else {
                 log::info!(
                     "{}[{}] New: {} ({})",
-                    new.prefix(),
+                    new.prefix(&self.profile.score),
                     new.company,
                     new,
                     new.url,
                 );
+                summary.new += 1;
+                summary.new_ids.push(id.clone());
+                self.events.push(NotifyEvent::NewJob {
+                    score: self.profile.score(id, new),
+                    job: new.clone(),
+                });
             }
         }
 
         // Insert the new jobs.
+        for id in jobs.keys() {
+            self.removed_jobs.remove(id);
+        }
         self.jobs.extend(jobs);
 
-        // Remove the stale jobs (missing for over 3 days).
-        self.jobs.retain(|_, job| {
-            job.source != job_source.name
-                || job
-                    .missing_since
-                    .map(|t| (now - t).num_days())
-                    .unwrap_or_default()
-                    < 3
+        if trusted {
+            summary.removed = self.remove_stale_jobs(&summary.name, now);
+        }
+        self.forget_removed_jobs(now);
+
+        Ok(summary)
+    }
+
+    /// Moves a job source's stale jobs (missing for over 3 days) to the removed jobs. Returns the
+    /// number of jobs moved.
+    fn remove_stale_jobs(&mut self, source: &str, now: DateTime<Utc>) -> usize {
+        let stale = self
+            .jobs
+            .iter()
+            .filter(|(_, job)| {
+                job.source == source && job.missing_since.is_some_and(|t| (now - t).num_days() >= 3)
+            })
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<_>>();
+        let removed = stale.len();
+        for id in stale {
+            let job = c!(self.jobs.remove(&id));
+            self.removed_jobs.insert(id, job);
+        }
+        removed
+    }
+
+    /// Forgets the removed jobs that have been missing for too long.
+    fn forget_removed_jobs(&mut self, now: DateTime<Utc>) {
+        self.removed_jobs.retain(|_, job| {
+            job.missing_since
+                .is_none_or(|t| (now - t).num_days() < Self::REMOVED_JOBS_DAYS)
         });
+    }
+}
 
-        Ok(())
+impl Drop for Bot {
+    fn drop(&mut self) {
+        // Clean up after a panic, or when `quit` wasn't called.
+        if let Some(driver) = self.driver.take() {
+            if let Ok(handle) = tokio::runtime::Handle::try_current() {
+                if handle.runtime_flavor() == RuntimeFlavor::MultiThread {
+                    tokio::task::block_in_place(|| {
+                        handle.block_on(async {
+                            let _ = driver.quit().await;
+                        })
+                    });
+                }
+            }
+        }
+        if let Some(mut server) = self.server.take() {
+            let _ = server.kill();
+            let _ = server.wait();
+        }
     }
 }
 
-fn sorted(jobs: &HashMap<String, Job>) -> impl IntoIterator<Item = (&String, &Job)> {
-    let mut ids = jobs.keys().collect::<Vec<_>>();
+/// Writes a file through a temporary file in the same directory, so that a crash while writing
+/// can't leave the file truncated.
+fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    std::fs::write(&tmp_path, contents)?;
+    std::fs::rename(tmp_path, path)
+}
+
+pub(crate) fn sorted<'a>(
+    jobs: impl IntoIterator<Item = (&'a String, &'a Job)>,
+    profile: &Profile,
+    sort: JobSort,
+) -> Vec<(&'a String, &'a Job)> {
+    let mut jobs = jobs.into_iter().collect::<Vec<_>>();
     let now = Utc::now();
-    ids.sort_by_key(|&id| {
-        let job = &jobs[id];
-        let age = (now - job.first_seen).num_days() as i32;
-        (
-            job.score() > 0,
-            age == 0,
-            age < 7,
-            job.score() - age,
-            &job.company,
-            &job.title,
-        )
-    });
-    ids.into_iter().map(|id| (id, &jobs[id]))
+    match sort {
+        JobSort::Rank => jobs.sort_by_key(|&(id, job)| {
+            let age = (now - job.first_seen).num_days() as i32;
+            let score = profile.score(id, job);
+            (
+                score > 0,
+                age == 0,
+                age < 7,
+                score - age,
+                &job.company,
+                &job.title,
+            )
+        }),
+        JobSort::Score => {
+            jobs.sort_by_key(|&(id, job)| (profile.score(id, job), job.first_seen, &job.title))
+        }
+        JobSort::Age => jobs.sort_by_key(|&(_, job)| (job.first_seen, &job.company, &job.title)),
+        JobSort::Company => jobs.sort_by_key(|&(_, job)| (&job.company, &job.title)),
+        JobSort::Title => jobs.sort_by_key(|&(_, job)| (&job.title, &job.company)),
+    }
+    jobs
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use url::Url;
+
+    use super::*;
+
+    #[test]
+    fn remove_stale_jobs() {
+        let now = Utc::now();
+        let url = Url::parse("https://example.com/jobs").unwrap();
+        let job = |source: &str, missing_days: Option<i64>| {
+            let mut job = Job::new(source, "Company", url.clone(), "Programmer");
+            job.missing_since = missing_days.map(|x| now - Duration::days(x));
+            job
+        };
+        let mut bot = Bot::default();
+        bot.jobs.insert("open".to_string(), job("A", None));
+        bot.jobs.insert("missing".to_string(), job("A", Some(2)));
+        bot.jobs.insert("stale".to_string(), job("A", Some(3)));
+        bot.jobs.insert("other".to_string(), job("B", Some(3)));
+        bot.removed_jobs
+            .insert("recent".to_string(), job("B", Some(29)));
+        bot.removed_jobs
+            .insert("old".to_string(), job("B", Some(30)));
+
+        assert_eq!(bot.remove_stale_jobs("A", now), 1);
+        bot.forget_removed_jobs(now);
+        let mut ids = bot.jobs.keys().collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(ids, ["missing", "open", "other"]);
+        let mut ids = bot.removed_jobs.keys().collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(ids, ["recent", "stale"]);
+    }
 }
@@ -0,0 +1,65 @@
+use std::{path::PathBuf, process::Command};
+
+use url::Url;
+
+/// A browser that can be driven over WebDriver.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, clap::ValueEnum)]
+pub enum Browser {
+    /// Firefox with geckodriver.
+    #[default]
+    Firefox,
+    /// Chrome or Chromium with chromedriver.
+    Chromium,
+}
+
+/// How to start or connect to a WebDriver server.
+#[derive(Debug)]
+pub struct BrowserConfig {
+    pub browser: Browser,
+    /// The WebDriver server binary, if it's not `geckodriver` or `chromedriver` on the path.
+    pub driver_path: Option<PathBuf>,
+    /// The port for the spawned WebDriver server to listen on.
+    pub port: u16,
+    /// An existing WebDriver server to connect to instead of spawning one.
+    pub remote_url: Option<Url>,
+}
+
+impl Default for BrowserConfig {
+    fn default() -> Self {
+        Self {
+            browser: Browser::default(),
+            driver_path: None,
+            port: 4444,
+            remote_url: None,
+        }
+    }
+}
+
+impl BrowserConfig {
+    /// Returns the command to spawn a WebDriver server, or `None` if connecting to a remote one.
+    pub fn server_command(&self) -> Option<Command> {
+        if self.remote_url.is_some() {
+            return None;
+        }
+
+        let default_path = match self.browser {
+            Browser::Firefox => "geckodriver",
+            Browser::Chromium => "chromedriver",
+        };
+        let mut command =
+            Command::new(self.driver_path.as_deref().unwrap_or(default_path.as_ref()));
+        match self.browser {
+            Browser::Firefox => command.args(["--port", &self.port.to_string()]),
+            Browser::Chromium => command.arg(format!("--port={}", self.port)),
+        };
+        Some(command)
+    }
+
+    /// Returns the URL of the WebDriver server.
+    pub fn server_url(&self) -> String {
+        match &self.remote_url {
+            Some(url) => url.to_string(),
+            None => format!("http://localhost:{}", self.port),
+        }
+    }
+}
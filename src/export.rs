@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use url::Url;
+
+use crate::{
+    job::{Job, JobDiscipline, JobLevel, JobSpecialty},
+    profile::Profile,
+    report::html_report,
+};
+
+/// A file format to export jobs in.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// The same RON format as the job database.
+    #[default]
+    Ron,
+    Csv,
+    /// A JSON array of jobs.
+    Json,
+    /// One JSON object per line.
+    Jsonl,
+    /// A Markdown table.
+    Markdown,
+    /// A self-contained HTML page like `list`, with sortable columns and filters.
+    Html,
+}
+
+const MARKDOWN_HEADER: &str =
+    "| Score | Age | Status | Company | Title | Level | Specialty | Discipline | Source |\n\
+     |------:|----:|--------|---------|-------|-------|-----------|------------|--------|\n";
+
+/// A job with its computed columns, as exported.
+#[derive(Serialize, Debug)]
+struct ExportRow<'a> {
+    id: &'a str,
+    score: i32,
+    age_days: i64,
+    /// The status as shown by `list`, rather than its serialized name.
+    status: Option<String>,
+    company: &'a str,
+    title: &'a str,
+    level: JobLevel,
+    specialty: Option<JobSpecialty>,
+    discipline: JobDiscipline,
+    is_general_application: bool,
+    source: &'a str,
+    url: &'a Url,
+    first_seen: DateTime<Utc>,
+    missing_since: Option<DateTime<Utc>>,
+}
+
+impl<'a> ExportRow<'a> {
+    fn new(id: &'a str, job: &'a Job, profile: &Profile, now: DateTime<Utc>) -> Self {
+        Self {
+            id,
+            score: profile.score(id, job),
+            age_days: (now - job.first_seen).num_days(),
+            status: profile.status(id).map(|x| x.to_string()),
+            company: &job.company,
+            title: &job.title,
+            level: job.level,
+            specialty: job.specialty,
+            discipline: job.discipline,
+            is_general_application: job.is_general_application,
+            source: &job.source,
+            url: &job.url,
+            first_seen: job.first_seen,
+            missing_since: job.missing_since,
+        }
+    }
+}
+
+impl ExportFormat {
+    /// Writes the jobs in this format, in the given order (except for RON).
+    pub fn export(&self, jobs: &[(&String, &Job)], profile: &Profile) -> String {
+        let now = Utc::now();
+        let rows = || {
+            jobs.iter()
+                .map(move |&(id, job)| ExportRow::new(id, job, profile, now))
+        };
+
+        match self {
+            ExportFormat::Ron => {
+                let jobs = jobs.iter().copied().collect::<HashMap<_, _>>();
+                ron::ser::to_string_pretty(&jobs, Default::default()).unwrap() + "\n"
+            }
+            ExportFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(vec![]);
+                for row in rows() {
+                    writer.serialize(row).unwrap();
+                }
+                String::from_utf8(writer.into_inner().unwrap()).unwrap()
+            }
+            ExportFormat::Json => {
+                serde_json::to_string_pretty(&rows().collect::<Vec<_>>()).unwrap() + "\n"
+            }
+            ExportFormat::Jsonl => rows()
+                .map(|row| serde_json::to_string(&row).unwrap() + "\n")
+                .collect(),
+            ExportFormat::Markdown => {
+                let mut md = MARKDOWN_HEADER.to_string();
+                for row in rows() {
+                    md += &format!(
+                        "| {} | {} days | {} | {} | [{}]({}) | {:?} | {} | {:?} | {} |\n",
+                        row.score,
+                        row.age_days,
+                        row.status.unwrap_or_default(),
+                        escape_markdown(row.company),
+                        escape_markdown(row.title),
+                        escape_markdown_url(row.url),
+                        row.level,
+                        row.specialty
+                            .map(|x| format!("{:?}", x))
+                            .unwrap_or_default(),
+                        row.discipline,
+                        escape_markdown(row.source),
+                    );
+                }
+                md
+            }
+            ExportFormat::Html => html_report(jobs, profile, |_, job| job.url.to_string()),
+        }
+    }
+}
+
+/// Escapes text to go in a Markdown table cell or link text.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '|' | '[' | ']' | '\\' | '*' | '_' | '`') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Percent-encodes the characters that would end a Markdown link or table cell early.
+fn escape_markdown_url(url: &Url) -> String {
+    url.as_str()
+        .replace('|', "%7C")
+        .replace('(', "%28")
+        .replace(')', "%29")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::JobStatus;
+
+    #[test]
+    fn export() {
+        let url = Url::parse("https://example.com/jobs/1?team=(art|code)").unwrap();
+        let job = Job::new(
+            "Source",
+            "Tom, Dick | Co",
+            url,
+            "Senior Graphics Programmer",
+        );
+        let id = "id".to_string();
+        let jobs = [(&id, &job)];
+        let mut profile = Profile::default();
+        profile
+            .jobs
+            .entry(id.clone())
+            .or_default()
+            .set_status(Some(JobStatus::Applied), None);
+
+        let csv = ExportFormat::Csv.export(&jobs, &profile);
+        let mut lines = csv.lines();
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("id,score,age_days,status,company,"));
+        assert!(lines
+            .next()
+            .unwrap()
+            .contains(",0,applied,\"Tom, Dick | Co\",Senior Graphics Programmer,Senior,Graphics,"));
+
+        let jsonl = ExportFormat::Jsonl.export(&jobs, &profile);
+        let row = serde_json::from_str::<serde_json::Value>(jsonl.trim()).unwrap();
+        assert_eq!(row["age_days"], 0);
+        assert_eq!(row["specialty"], "Graphics");
+        assert_eq!(row["status"], "applied");
+
+        let md = ExportFormat::Markdown.export(&jobs, &profile);
+        assert!(md.contains(
+            "| applied | Tom, Dick \\| Co | [Senior Graphics Programmer]\
+             (https://example.com/jobs/1?team=%28art%7Ccode%29) |"
+        ));
+    }
+}
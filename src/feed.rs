@@ -0,0 +1,118 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use html_escape::{encode_double_quoted_attribute, encode_text};
+use url::form_urlencoded::byte_serialize;
+
+use crate::job::Job;
+
+/// An Atom feed of jobs, newest first.
+pub struct Feed<'a> {
+    pub title: String,
+    /// The jobs with their IDs and scores.
+    pub jobs: Vec<(&'a str, i32, &'a Job)>,
+}
+
+impl<'a> Feed<'a> {
+    pub fn new(
+        title: impl Into<String>,
+        jobs: impl IntoIterator<Item = (&'a str, i32, &'a Job)>,
+    ) -> Self {
+        let mut jobs = jobs.into_iter().collect::<Vec<_>>();
+        jobs.sort_by_key(|&(_, _, job)| std::cmp::Reverse(job.first_seen));
+        Self {
+            title: title.into(),
+            jobs,
+        }
+    }
+
+    pub fn to_atom(&self) -> String {
+        let updated = self
+            .jobs
+            .first()
+            .map_or_else(Utc::now, |(_, _, job)| job.first_seen);
+        let mut xml = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+             <feed xmlns=\"http://www.w3.org/2005/Atom\">\n\
+             <id>urn:find-a-job:{}</id>\n\
+             <title>{}</title>\n\
+             <updated>{}</updated>\n\
+             <generator>find_a_job</generator>\n",
+            encode_text(&self.title.to_lowercase().replace(' ', "-")),
+            encode_text(&self.title),
+            timestamp(updated),
+        );
+        for (id, score, job) in &self.jobs {
+            let mut content = format!(
+                "Score: {}\nLevel: {:?}\nDiscipline: {:?}\n",
+                score, job.level, job.discipline,
+            );
+            if let Some(specialty) = job.specialty {
+                content += &format!("Specialty: {:?}\n", specialty);
+            }
+            if job.is_general_application {
+                content += "General application\n";
+            }
+            content += &format!("Source: {}\n", job.source);
+
+            xml += &format!(
+                "<entry>\n\
+                 <id>urn:find-a-job:job:{}</id>\n\
+                 <title>[{}] {}</title>\n\
+                 <link href=\"{}\"/>\n\
+                 <author><name>{}</name></author>\n\
+                 <published>{}</published>\n\
+                 <updated>{}</updated>\n\
+                 <content type=\"text\">{}</content>\n\
+                 </entry>\n",
+                encode_text(&byte_serialize(id.as_bytes()).collect::<String>()),
+                encode_text(&job.company),
+                encode_text(&job.title),
+                encode_double_quoted_attribute(job.url.as_str()),
+                encode_text(&job.company),
+                timestamp(job.first_seen),
+                timestamp(job.first_seen),
+                encode_text(&content),
+            );
+        }
+        xml += "</feed>\n";
+        xml
+    }
+}
+
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use url::Url;
+
+    use super::*;
+
+    #[test]
+    fn atom() {
+        let url = Url::parse("https://example.com/jobs/1?a=1&b=2").unwrap();
+        let mut old = Job::new(
+            "Source",
+            "Tom & Co",
+            url.clone(),
+            "Senior Graphics Programmer",
+        );
+        old.first_seen -= Duration::days(3);
+        let new = Job::new("Source", "Company", url, "Gameplay Programmer");
+        let feed = Feed::new("Game Jobs", [("Source/1", 6, &old), ("Source/2", 2, &new)]);
+        assert_eq!(feed.jobs[0].1, 2);
+
+        let xml = feed.to_atom();
+        assert!(xml.contains("<id>urn:find-a-job:game-jobs</id>"));
+        // Jobs with the same URL still get their own entries.
+        assert!(xml.contains("<id>urn:find-a-job:job:Source%2F1</id>"));
+        assert!(xml.contains("<id>urn:find-a-job:job:Source%2F2</id>"));
+        assert!(xml.contains("<title>[Tom &amp; Co] Senior Graphics Programmer</title>"));
+        assert!(xml.contains("<link href=\"https://example.com/jobs/1?a=1&amp;b=2\"/>"));
+        assert!(
+            xml.contains("Score: 6\nLevel: Senior\nDiscipline: Programmer\nSpecialty: Graphics")
+        );
+        assert_eq!(xml.matches("<entry>").count(), 2);
+    }
+}
@@ -0,0 +1,174 @@
+use chrono::Utc;
+use regex::Regex;
+
+use crate::{
+    job::{Job, JobDiscipline, JobLevel, JobSpecialty},
+    profile::{JobStatus, Profile},
+};
+
+/// Criteria for choosing which jobs to list, and in what order.
+#[derive(Default, Debug)]
+pub struct JobFilter {
+    /// Only include jobs with one of these statuses, even if they're missing or removed.
+    ///
+    /// If empty, only open jobs are included, minus the ones that are hidden or
+    /// [closed](JobStatus::is_closed).
+    pub statuses: Vec<JobStatus>,
+    /// Include jobs that are hidden, snoozed, or from a muted company or source.
+    pub show_hidden: bool,
+    /// Include jobs that are missing from their job source.
+    pub include_missing: bool,
+    /// Only include jobs with one of these levels.
+    pub levels: Vec<JobLevel>,
+    /// Only include jobs with one of these disciplines.
+    pub disciplines: Vec<JobDiscipline>,
+    /// Only include jobs with one of these specialties.
+    pub specialties: Vec<JobSpecialty>,
+    /// Only include jobs from a company containing one of these names (case-insensitive).
+    pub companies: Vec<String>,
+    /// Only include jobs from one of these job sources (case-insensitive).
+    pub sources: Vec<String>,
+    /// Only include jobs with at least this score.
+    pub min_score: Option<i32>,
+    /// Only include jobs first seen at least this many days ago.
+    pub min_age: Option<i64>,
+    /// Only include jobs first seen at most this many days ago.
+    pub max_age: Option<i64>,
+    /// Only include jobs with a title matching this regex.
+    pub title_re: Option<Regex>,
+    /// The order to list jobs in.
+    pub sort: JobSort,
+    /// Reverse the order to list jobs in.
+    pub reverse: bool,
+    /// Only include the last jobs in the order, so the most relevant jobs are kept.
+    pub limit: Option<usize>,
+}
+
+impl JobFilter {
+    /// True if jobs that have been removed from the job database should be considered.
+    pub fn includes_removed(&self) -> bool {
+        !self.statuses.is_empty()
+    }
+
+    pub fn matches(&self, id: &str, job: &Job, profile: &Profile) -> bool {
+        let status = profile.status(id);
+        if self.statuses.is_empty() {
+            if !self.include_missing && job.missing_since.is_some() {
+                return false;
+            }
+            if !self.show_hidden && profile.is_hidden(id, job) {
+                return false;
+            }
+            if status.is_some_and(JobStatus::is_closed) {
+                return false;
+            }
+        } else if !status.is_some_and(|x| self.statuses.contains(&x)) {
+            return false;
+        }
+
+        let age = (Utc::now() - job.first_seen).num_days();
+        (self.levels.is_empty() || self.levels.contains(&job.level))
+            && (self.disciplines.is_empty() || self.disciplines.contains(&job.discipline))
+            && (self.specialties.is_empty()
+                || job.specialty.is_some_and(|x| self.specialties.contains(&x)))
+            && (self.companies.is_empty()
+                || self
+                    .companies
+                    .iter()
+                    .any(|x| job.company.to_lowercase().contains(&x.to_lowercase())))
+            && (self.sources.is_empty()
+                || self
+                    .sources
+                    .iter()
+                    .any(|x| x.eq_ignore_ascii_case(&job.source)))
+            && self.min_score.is_none_or(|x| profile.score(id, job) >= x)
+            && self.min_age.is_none_or(|x| age >= x)
+            && self.max_age.is_none_or(|x| age <= x)
+            && self
+                .title_re
+                .as_ref()
+                .is_none_or(|x| x.is_match(&job.title))
+    }
+}
+
+/// An order to list jobs in, from least to most relevant.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, clap::ValueEnum)]
+pub enum JobSort {
+    /// By score and age, with a preference for new jobs.
+    #[default]
+    Rank,
+    /// By score.
+    Score,
+    /// By age, with the newest jobs last.
+    Age,
+    /// By company name.
+    Company,
+    /// By job title.
+    Title,
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use super::*;
+
+    #[test]
+    fn matches() {
+        let url = Url::parse("https://example.com").unwrap();
+        let job = Job::new("Source", "Company", url, "Gameplay Programmer");
+        let mut profile = Profile::default();
+        let filter = JobFilter::default();
+        assert!(filter.matches("id", &job, &profile));
+
+        // Closed jobs are only listed when filtering by status.
+        profile
+            .jobs
+            .entry("id".to_string())
+            .or_default()
+            .set_status(Some(JobStatus::Applied), None);
+        assert!(!filter.matches("id", &job, &profile));
+        let filter = JobFilter {
+            statuses: vec![JobStatus::Applied],
+            ..Default::default()
+        };
+        assert!(filter.matches("id", &job, &profile));
+
+        // Muted companies are only listed when showing hidden jobs.
+        let mut profile = Profile::default();
+        profile.muted_companies.insert("company".to_string());
+        assert!(!JobFilter::default().matches("id", &job, &profile));
+        let filter = JobFilter {
+            show_hidden: true,
+            ..Default::default()
+        };
+        assert!(filter.matches("id", &job, &profile));
+
+        let filter = JobFilter {
+            levels: vec![JobLevel::Senior],
+            ..Default::default()
+        };
+        assert!(!filter.matches("id", &job, &profile));
+        let filter = JobFilter {
+            title_re: Some(Regex::new("(?i)gameplay").unwrap()),
+            companies: vec!["comp".to_string()],
+            show_hidden: true,
+            ..Default::default()
+        };
+        assert!(filter.matches("id", &job, &profile));
+
+        // Score overrides replace the computed score.
+        let filter = JobFilter {
+            min_score: Some(10),
+            ..Default::default()
+        };
+        let mut profile = Profile::default();
+        assert!(!filter.matches("id", &job, &profile));
+        profile
+            .jobs
+            .entry("id".to_string())
+            .or_default()
+            .score_override = Some(10);
+        assert!(filter.matches("id", &job, &profile));
+    }
+}
@@ -0,0 +1,208 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The outcome of scraping a job source once.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SourceRun {
+    pub time: DateTime<Utc>,
+    /// The number of jobs found.
+    pub jobs: usize,
+    /// The number of pages visited.
+    pub pages: usize,
+    /// How long the scrape took, in seconds.
+    pub seconds: f64,
+    /// The error that stopped the scrape, if any.
+    pub error: Option<String>,
+}
+
+/// The recent run history of a job source.
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct SourceHealth {
+    /// The most recent runs, oldest first.
+    pub runs: Vec<SourceRun>,
+}
+
+impl SourceHealth {
+    /// The number of runs to remember per job source.
+    const MAX_RUNS: usize = 30;
+
+    pub fn push(&mut self, run: SourceRun) {
+        self.runs.push(run);
+        self.runs
+            .drain(..self.runs.len().saturating_sub(Self::MAX_RUNS));
+    }
+
+    /// The number of failed runs in a row, counting back from the most recent run.
+    pub fn failures(&self) -> usize {
+        self.runs
+            .iter()
+            .rev()
+            .take_while(|x| x.error.is_some())
+            .count()
+    }
+
+    /// Checks the most recent run for signs that the job source is broken.
+    ///
+    /// The number of jobs found is compared to the last successful run before it, or to the
+    /// number of jobs from the job source that were open before the run if there isn't one. So a
+    /// drop in the number of jobs is only trusted once it shows up twice in a row. Finding no jobs
+    /// is never trusted while the job source still has open jobs.
+    pub fn check(&self, open_jobs: usize, config: &HealthConfig) -> Option<Anomaly> {
+        let (run, previous) = self.runs.split_last()?;
+        if let Some(error) = &run.error {
+            let failures = self.failures();
+            return (failures >= config.max_failures).then(|| Anomaly::Failing {
+                failures,
+                error: error.clone(),
+            });
+        }
+
+        let before = previous
+            .iter()
+            .rev()
+            .find(|x| x.error.is_none())
+            .map_or(open_jobs, |x| x.jobs);
+        if run.jobs == 0 {
+            let before = before.max(open_jobs);
+            (before > 0).then_some(Anomaly::NoJobs { before })
+        } else if before == 0 {
+            None
+        } else if before.saturating_sub(run.jobs) * 100 > before * config.max_drop_percent {
+            Some(Anomaly::Drop {
+                before,
+                after: run.jobs,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Thresholds for deciding that a job source is broken.
+#[derive(Debug)]
+pub struct HealthConfig {
+    /// The largest drop in the number of jobs that's still trusted, as a percentage.
+    pub max_drop_percent: usize,
+    /// The number of failed runs in a row before warning.
+    pub max_failures: usize,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            max_drop_percent: 50,
+            max_failures: 3,
+        }
+    }
+}
+
+/// A sign that a job source is broken, probably because its site changed.
+#[derive(Serialize, Deserialize, Debug)]
+pub enum Anomaly {
+    NoJobs { before: usize },
+    Drop { before: usize, after: usize },
+    Failing { failures: usize, error: String },
+}
+
+impl Anomaly {
+    /// True if the jobs that weren't found shouldn't be marked missing.
+    pub fn is_untrusted(&self) -> bool {
+        matches!(self, Self::NoJobs { .. } | Self::Drop { .. })
+    }
+}
+
+impl Display for Anomaly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoJobs { before } => write!(f, "Found no jobs (had {})", before),
+            Self::Drop { before, after } => {
+                write!(f, "Found {} jobs (had {})", after, before)
+            }
+            Self::Failing { failures, error } => {
+                write!(f, "Failed {} times in a row: {}", failures, error)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(jobs: usize, error: Option<&str>) -> SourceRun {
+        SourceRun {
+            time: Utc::now(),
+            jobs,
+            pages: 1,
+            seconds: 1.0,
+            error: error.map(|x| x.to_string()),
+        }
+    }
+
+    #[test]
+    fn check() {
+        let config = HealthConfig::default();
+        let mut health = SourceHealth::default();
+        assert!(health.check(10, &config).is_none());
+
+        // The first run is compared to the open jobs.
+        health.push(run(10, None));
+        assert!(health.check(10, &config).is_none());
+        assert!(health.check(20, &config).is_none());
+        assert!(matches!(
+            health.check(21, &config),
+            Some(Anomaly::Drop {
+                before: 21,
+                after: 10,
+            }),
+        ));
+
+        // Later runs are compared to the last successful run.
+        health.push(run(0, None));
+        assert!(matches!(
+            health.check(0, &config),
+            Some(Anomaly::NoJobs { before: 10 }),
+        ));
+
+        // Finding no jobs again isn't trusted while there are open jobs.
+        health.push(run(0, None));
+        assert!(matches!(
+            health.check(10, &config),
+            Some(Anomaly::NoJobs { before: 10 }),
+        ));
+        assert!(health.check(0, &config).is_none());
+
+        health.push(run(0, Some("timeout")));
+        health.push(run(0, Some("timeout")));
+        assert!(health.check(10, &config).is_none());
+        health.push(run(0, Some("timeout")));
+        assert_eq!(health.failures(), 3);
+        assert!(matches!(
+            health.check(10, &config),
+            Some(Anomaly::Failing { failures: 3, .. }),
+        ));
+    }
+
+    #[test]
+    fn check_repeated_drop() {
+        let config = HealthConfig::default();
+        let mut health = SourceHealth::default();
+        health.push(run(40, None));
+        health.push(run(10, None));
+        assert!(matches!(
+            health.check(40, &config),
+            Some(Anomaly::Drop {
+                before: 40,
+                after: 10,
+            }),
+        ));
+
+        // The jobs weren't marked missing, but the lower count is trusted the second time.
+        health.push(run(0, Some("timeout")));
+        health.push(run(10, None));
+        assert!(health.check(40, &config).is_none());
+    }
+}
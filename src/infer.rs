@@ -0,0 +1,253 @@
+use std::collections::{HashMap, HashSet};
+
+use scraper::{ElementRef, Html};
+use url::Url;
+
+use crate::author::SourceAuthor;
+
+/// Finds the repeated job cards in a page and proposes a job source that extracts them.
+pub(crate) fn infer_job_source(url: &Url, html: &str) -> Option<SourceAuthor> {
+    let doc = Html::parse_document(html);
+    let cards = find_cards(&doc)?;
+    log::info!(
+        "Found {} job cards like: {}",
+        cards.len(),
+        truncated_tag(&cards[0].element.html()),
+    );
+
+    // The jobs that a good job source should extract.
+    let expected_titles = cards
+        .iter()
+        .map(|x| normalized(&x.title))
+        .collect::<HashSet<_>>();
+    let expected_urls = cards
+        .iter()
+        .filter_map(|x| url.join(&x.href).ok())
+        .collect::<HashSet<_>>();
+
+    // Try every combination of candidate regexes and keep the one that extracts the most jobs.
+    let mut best = None;
+    let mut best_score = 0;
+    for (next_job, start) in next_job_candidates(&cards[0]) {
+        for title in title_candidates(&cards[0]) {
+            for job_url in url_candidates(&cards[0]) {
+                let mut author = SourceAuthor::new(url, html.to_string());
+                if let Some((start_re, end_re)) = &start {
+                    author.set("start_re", start_re);
+                    author.set("end_re", end_re);
+                }
+                author.set("next_job_re", &next_job);
+                author.set("job_title_re", &title);
+                author.set("job_url_re", &job_url);
+                let Ok(job_source) = author.parse() else {
+                    continue;
+                };
+
+                let jobs = job_source.parse_page(url, html);
+                let score = jobs
+                    .values()
+                    .filter(|x| expected_titles.contains(&normalized(&x.title)))
+                    .count()
+                    + jobs
+                        .values()
+                        .filter(|x| expected_urls.contains(&x.url))
+                        .count();
+                if score > best_score {
+                    best = Some(author);
+                    best_score = score;
+                }
+            }
+        }
+    }
+
+    best
+}
+
+/// A repeated element in a list of jobs.
+struct Card<'a> {
+    element: ElementRef<'a>,
+    /// The link to the job page.
+    link: ElementRef<'a>,
+    /// The element whose own text is the job title.
+    title_element: ElementRef<'a>,
+    title: String,
+    href: String,
+}
+
+/// Finds the largest group of sibling elements that look like job cards.
+fn find_cards(doc: &Html) -> Option<Vec<Card<'_>>> {
+    let mut best = None;
+    let mut best_score = 0;
+    for parent in doc
+        .root_element()
+        .descendants()
+        .filter_map(ElementRef::wrap)
+    {
+        // Navigation links are repeated too, but they're not jobs.
+        if parent
+            .ancestors()
+            .chain([*parent])
+            .filter_map(ElementRef::wrap)
+            .any(|x| matches!(x.value().name(), "nav" | "header" | "footer"))
+        {
+            continue;
+        }
+
+        // Group the children by tag name and first class.
+        let mut groups = HashMap::<_, Vec<_>>::new();
+        for child in parent.children().filter_map(ElementRef::wrap) {
+            let key = (child.value().name(), first_class(child));
+            groups.entry(key).or_default().extend(card(child));
+        }
+
+        for cards in groups.into_values() {
+            if cards.len() < 3 {
+                continue;
+            }
+            // Job titles are longer than most other links.
+            let text_len = cards.iter().map(|x| x.title.len()).sum::<usize>() / cards.len();
+            let score = cards.len() * text_len.min(40);
+            if score > best_score {
+                best = Some(cards);
+                best_score = score;
+            }
+        }
+    }
+
+    best
+}
+
+/// Finds the job link and title in a potential job card.
+fn card(element: ElementRef<'_>) -> Option<Card<'_>> {
+    element
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|x| x.value().name() == "a")
+        .find_map(|link| {
+            let href = link.value().attr("href")?;
+            if href.starts_with('#') || href.starts_with("javascript:") {
+                return None;
+            }
+            let (title_element, title) = link
+                .descendants()
+                .filter_map(ElementRef::wrap)
+                .map(|x| (x, own_text(x)))
+                .max_by_key(|(_, text)| text.len())?;
+            (!title.is_empty()).then(|| Card {
+                element,
+                link,
+                title_element,
+                title,
+                href: href.to_string(),
+            })
+        })
+}
+
+/// Proposes a `next_job_re`, with a `start_re` and `end_re` if the card itself isn't distinctive.
+fn next_job_candidates(card: &Card) -> Vec<(String, Option<(String, String)>)> {
+    let mut candidates = vec![];
+    let tag = card.element.value().name();
+    if let Some(class) = first_class(card.element) {
+        candidates.push((tag_re(tag, Some(class)), None));
+    }
+    if let Some(parent) = card.element.parent().and_then(ElementRef::wrap) {
+        let parent_tag = parent.value().name();
+        if let Some(class) = first_class(parent) {
+            let start = (
+                tag_re(parent_tag, Some(class)),
+                format!("</{}>", parent_tag),
+            );
+            candidates.push((tag_re(tag, None), Some(start)));
+        }
+    }
+    candidates.push((tag_re(tag, None), None));
+    candidates
+}
+
+fn title_candidates(card: &Card) -> Vec<String> {
+    let tag = card.title_element.value().name();
+    let mut candidates = vec![];
+    if let Some(class) = first_class(card.title_element) {
+        candidates.push(format!(r"{}\s*([^<]+)", tag_re(tag, Some(class))));
+    }
+    candidates.push(format!(r"{}\s*([^<]+)", tag_re(tag, None)));
+    candidates
+}
+
+fn url_candidates(card: &Card) -> Vec<String> {
+    let href = r#"\bhref=["']([^"']*)["']"#;
+    let mut candidates = vec![];
+    if let Some(class) = first_class(card.link) {
+        let class = class_re(class);
+        candidates.push(format!(r"<a\b[^>]*{}[^>]*{}", class, href));
+        candidates.push(format!(r"<a\b[^>]*{}[^>]*{}", href, class));
+    }
+    candidates.push(format!(r"<a\b[^>]*{}", href));
+    candidates
+}
+
+/// Returns a regex for the opening tag of an element, optionally with a class.
+fn tag_re(tag: &str, class: Option<&str>) -> String {
+    match class {
+        Some(class) => format!(r"<{}\b[^>]*{}[^>]*>", tag, class_re(class)),
+        None => format!(r"<{}\b[^>]*>", tag),
+    }
+}
+
+/// Returns a regex for a class attribute containing a class.
+fn class_re(class: &str) -> String {
+    format!(
+        r#"\bclass=["'](?:[^"']*\s)?{}(?:\s[^"']*)?["']"#,
+        regex::escape(class),
+    )
+}
+
+/// Returns the first class of an element, in the order it's written in the HTML.
+fn first_class(element: ElementRef<'_>) -> Option<&str> {
+    element.value().attr("class")?.split_whitespace().next()
+}
+
+fn own_text(element: ElementRef) -> String {
+    normalized(
+        &element
+            .children()
+            .filter_map(|x| x.value().as_text())
+            .map(|x| &**x)
+            .collect::<String>(),
+    )
+}
+
+fn normalized(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn truncated_tag(html: &str) -> &str {
+    html.find('>').map_or(html, |i| &html[..=i])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infer() {
+        let html = r#"<html><body>
+<nav><ul><li><a href="/">Home</a></li><li><a href="/about">About</a></li><li><a href="/jobs">Jobs</a></li></ul></nav>
+<ul class="openings">
+  <li class="opening featured"><a class="link" href="/jobs/1"><h3>Gameplay Programmer</h3></a><span>Remote</span></li>
+  <li class="opening"><a class="link" href="/jobs/2"><h3>Senior &amp; Lead Artist</h3></a><span>Austin</span></li>
+  <li class="opening"><a class="link" href="/jobs/3"><h3>QA Tester</h3></a><span>Remote</span></li>
+</ul>
+</body></html>"#;
+        let url = Url::parse("https://example.com/careers").unwrap();
+        let job_source = infer_job_source(&url, html).unwrap().parse().unwrap();
+        let jobs = job_source.parse_page(&url, html);
+        let mut titles = jobs.values().map(|x| x.title.as_str()).collect::<Vec<_>>();
+        titles.sort();
+        assert_eq!(
+            titles,
+            ["Gameplay Programmer", "QA Tester", "Senior & Lead Artist"],
+        );
+        assert!(jobs.contains_key("https://example.com/jobs/2"));
+    }
+}
@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{collections::BTreeMap, fmt::Display};
 
 use chrono::{DateTime, Utc};
 use colored::{ColoredString, Colorize as _};
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use url::Url;
 
 /// A discovered job posting.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct Job {
     /// The first time the job was seen.
@@ -69,50 +69,48 @@ impl Job {
         self.is_general_application = parse_is_general_application(&norm);
     }
 
-    // TODO: Load preferences from a config file.
-    pub fn score(&self) -> i32 {
-        let mut score = 0;
+    pub fn score(&self, config: &ScoreConfig) -> i32 {
+        self.score_breakdown(config)
+            .into_iter()
+            .map(|(_, x)| x)
+            .sum()
+    }
+
+    /// Returns each part of the job's score, labeled by the job field it comes from.
+    pub fn score_breakdown(&self, config: &ScoreConfig) -> Vec<(&'static str, i32)> {
+        let mut breakdown = vec![];
 
         if self.is_general_application {
-            score -= 30;
+            breakdown.push(("is_general_application", config.general_application));
+        }
+        breakdown.push((
+            "level",
+            config.level.get(&self.level).copied().unwrap_or_default(),
+        ));
+        breakdown.push((
+            "discipline",
+            config
+                .discipline
+                .get(&self.discipline)
+                .copied()
+                .unwrap_or_default(),
+        ));
+        if let Some(specialty) = self.specialty {
+            breakdown.push((
+                "specialty",
+                config
+                    .specialty
+                    .get(&specialty)
+                    .copied()
+                    .unwrap_or_default(),
+            ));
         }
-        score += match self.level {
-            JobLevel::Intern => -2000,
-            JobLevel::Entry => 5,
-            JobLevel::Mid => 0,
-            JobLevel::Senior => -1000,
-            JobLevel::Lead => -3000,
-        };
-        score += match self.discipline {
-            JobDiscipline::Programmer => 1,
-            JobDiscipline::Designer => -1000,
-            JobDiscipline::Artist => -1000,
-            JobDiscipline::Writer => -2000,
-            JobDiscipline::Composer => -2000,
-            JobDiscipline::Tester => -3000,
-            JobDiscipline::Manager => -5000,
-            JobDiscipline::Other => -2000,
-        };
-        score += match self.specialty {
-            Some(JobSpecialty::Gameplay) => 5,
-            Some(JobSpecialty::Graphics) => 1,
-            Some(JobSpecialty::Engine) => 1,
-            Some(JobSpecialty::Physics) => -2,
-            Some(JobSpecialty::Animation) => -5,
-            Some(JobSpecialty::Ai) => -5,
-            Some(JobSpecialty::Audio) => -10,
-            Some(JobSpecialty::Ui) => -15,
-            Some(JobSpecialty::Network) => -20,
-            Some(JobSpecialty::Automation) => -20,
-            Some(JobSpecialty::Web) => -20,
-            None => 0,
-        };
-
-        score
+
+        breakdown
     }
 
-    pub(crate) fn prefix(&self) -> ColoredString {
-        if self.score() > 0 {
+    pub(crate) fn prefix(&self, config: &ScoreConfig) -> ColoredString {
+        if self.score(config) > 0 {
             "[!] ".bold().green()
         } else {
             "".into()
@@ -120,7 +118,73 @@ impl Job {
     }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+/// Preferences for scoring jobs.
+///
+/// Missing fields fall back to the defaults, but missing entries in a present field score 0.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScoreConfig {
+    /// The score for an application drop box.
+    pub general_application: i32,
+    /// The score for each job level.
+    pub level: BTreeMap<JobLevel, i32>,
+    /// The score for each job discipline.
+    pub discipline: BTreeMap<JobDiscipline, i32>,
+    /// The score for each job specialty.
+    pub specialty: BTreeMap<JobSpecialty, i32>,
+}
+
+impl Default for ScoreConfig {
+    fn default() -> Self {
+        Self {
+            general_application: -30,
+            level: BTreeMap::from([
+                (JobLevel::Intern, -2000),
+                (JobLevel::Entry, 5),
+                (JobLevel::Mid, 0),
+                (JobLevel::Senior, -1000),
+                (JobLevel::Lead, -3000),
+            ]),
+            discipline: BTreeMap::from([
+                (JobDiscipline::Programmer, 1),
+                (JobDiscipline::Designer, -1000),
+                (JobDiscipline::Artist, -1000),
+                (JobDiscipline::Writer, -2000),
+                (JobDiscipline::Composer, -2000),
+                (JobDiscipline::Tester, -3000),
+                (JobDiscipline::Manager, -5000),
+                (JobDiscipline::Other, -2000),
+            ]),
+            specialty: BTreeMap::from([
+                (JobSpecialty::Gameplay, 5),
+                (JobSpecialty::Graphics, 1),
+                (JobSpecialty::Engine, 1),
+                (JobSpecialty::Physics, -2),
+                (JobSpecialty::Animation, -5),
+                (JobSpecialty::Ai, -5),
+                (JobSpecialty::Audio, -10),
+                (JobSpecialty::Ui, -15),
+                (JobSpecialty::Network, -20),
+                (JobSpecialty::Automation, -20),
+                (JobSpecialty::Web, -20),
+            ]),
+        }
+    }
+}
+
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Debug,
+    clap::ValueEnum,
+)]
 pub enum JobLevel {
     Intern,
     Entry,
@@ -129,7 +193,19 @@ pub enum JobLevel {
     Lead,
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Debug,
+    clap::ValueEnum,
+)]
 pub enum JobSpecialty {
     Gameplay,
     Graphics,
@@ -144,7 +220,19 @@ pub enum JobSpecialty {
     Web,
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Debug,
+    clap::ValueEnum,
+)]
 pub enum JobDiscipline {
     Programmer,
     Designer,
@@ -165,14 +253,6 @@ fn normalized(s: impl AsRef<str>) -> String {
         .join(" ")
 }
 
-macro_rules! re {
-    ($name:ident, $($e:expr),* $(,)?) => {
-        static $name: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(
-            || regex::Regex::new(concat!($($e),*)).unwrap(),
-        );
-    };
-}
-
 fn parse_level(norm: &str) -> JobLevel {
     re!(
         INTERN_RE,
@@ -355,6 +435,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn score() {
+        let url = Url::parse("https://example.com").unwrap();
+        let job = Job::new("", "", url, "Gameplay Programmer");
+        assert_eq!(job.score(&ScoreConfig::default()), 6);
+
+        // Missing fields fall back to the defaults, but missing entries score 0.
+        let config: ScoreConfig = ron::from_str("(level: {Mid: 7})").unwrap();
+        assert_eq!(job.score(&config), 13);
+        let config: ScoreConfig = ron::from_str("(specialty: {})").unwrap();
+        assert_eq!(job.score(&config), 1);
+    }
+
     const TEST_CASES: [(&str, JobLevel, Option<JobSpecialty>, JobDiscipline); 93] = [
         (
             "Software Engineer Intern - Automation",
@@ -1,9 +1,19 @@
-use std::{collections::HashMap, fmt::Display, time::Duration};
+use std::{
+    collections::{hash_map::Entry, BTreeMap, HashMap},
+    fmt::Display,
+    time::{Duration, Instant},
+};
 
+use glob::{MatchOptions, Pattern};
 use html_escape::decode_html_entities;
 use regex::Regex;
-use serde::{Deserialize, Serialize};
+use ron::{
+    de::Deserializer,
+    error::{Position, SpannedError},
+};
+use serde::{de::IgnoredAny, Deserialize, Serialize};
 use thirtyfour::{
+    components::SelectElement,
     error::{WebDriverError, WebDriverResult},
     prelude::{ElementQueryable as _, ElementWaitable as _},
     By, WebDriver, WebElement,
@@ -17,10 +27,24 @@ use crate::job::Job;
 #[serde(deny_unknown_fields)]
 pub struct JobSource {
     pub name: String,
-    url: Url,
+    pub url: Url,
+    /// Optional extra URLs to scrape, such as one per department or office.
+    #[serde(default)]
+    more_urls: Vec<Url>,
+    /// Optional sets of query parameters to scrape every URL with, replacing any parameters
+    /// with the same name.
+    #[serde(default)]
+    query_variants: Vec<BTreeMap<String, String>>,
     /// A sequence of sub-DOMs to enter to get to the meat.
     #[serde(default)]
     sub_doms: Vec<SubDom>,
+    /// Browser actions to run in order after loading the first page from each URL, such as
+    /// accepting cookies or choosing a filter. These run in the top-level document.
+    #[serde(default)]
+    actions: Vec<Action>,
+    /// Browser actions to run in order inside the sub-DOMs, after `actions`.
+    #[serde(default)]
+    sub_dom_actions: Vec<Action>,
     /// An optional CSS selector to wait for before parsing the HTML.
     #[serde(default)]
     wait_for: Option<String>,
@@ -48,9 +72,144 @@ pub struct JobSource {
     /// An optional CSS selector to close a popup before going to the next page.
     #[serde(default)]
     close_popup: Option<String>,
-    /// An optional CSS selector to navigate to the next page.
+    /// An optional CSS selector for a button to navigate to the next page.
+    ///
+    /// This is shorthand for `pagination: Some(NextButton(..))`.
     #[serde(default)]
     next_page: Option<String>,
+    /// An optional way to get more jobs after the first page.
+    #[serde(default)]
+    pagination: Option<Pagination>,
+    /// The most pages to scrape, or times to load more jobs (default 100).
+    #[serde(default)]
+    max_pages: Option<usize>,
+    /// Timeouts, retries and delays for scraping.
+    #[serde(default)]
+    timing: Timing,
+}
+
+/// A way to get more jobs after the first page.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+enum Pagination {
+    /// Click the button matching a CSS selector and wait for the URL to change.
+    NextButton(String),
+    /// Follow the href of the link matching a CSS selector.
+    NextLink(String),
+    /// Replace `{page}` in a URL template with `start + n * step` for the nth page, counting the
+    /// first page (the source's `url`) as 0. Stops at the first page without new jobs.
+    UrlTemplate {
+        template: String,
+        start: usize,
+        step: usize,
+    },
+    /// Click the button matching a CSS selector until the number of jobs stops growing.
+    LoadMore(String),
+    /// Scroll to the bottom of the page until the number of jobs stops growing.
+    Scroll,
+}
+
+/// A browser action to run before parsing jobs.
+#[derive(Serialize, Deserialize, Debug)]
+enum Action {
+    /// Click the element matching a CSS selector, or do nothing if it doesn't show up soon.
+    Click(String),
+    /// Type text into the element matching a CSS selector, replacing its value.
+    Type(String, String),
+    /// Choose an option by its visible text in the `<select>` matching a CSS selector.
+    Select(String, String),
+    /// Wait for an element matching a CSS selector to show up.
+    WaitFor(String),
+    /// Run some JavaScript.
+    Js(String),
+    /// Wait for a number of milliseconds.
+    Sleep(u64),
+}
+
+impl Action {
+    /// How long to wait for an element to click, since it may never show up.
+    const OPTIONAL_WAIT: Duration = Duration::from_secs(1);
+
+    async fn run(
+        &self,
+        driver: &WebDriver,
+        root: &WebElement,
+        wait: Duration,
+    ) -> WebDriverResult<()> {
+        let interval = Duration::from_millis(100);
+        match self {
+            Action::Click(css) => {
+                // Popups like cookie banners may have been dismissed already.
+                let optional_wait = wait.min(Self::OPTIONAL_WAIT);
+                let query = root.query(By::Css(css)).wait(optional_wait, interval);
+                let Ok(elem) = query.first().await else {
+                    log::debug!("Nothing to click: {}", css);
+                    return Ok(());
+                };
+                elem.wait_until().wait(wait, interval).clickable().await?;
+                scroll_into_view(driver, &elem).await?;
+                elem.click().await?;
+            }
+            Action::Type(css, text) => {
+                let elem = root
+                    .query(By::Css(css))
+                    .wait(wait, interval)
+                    .first()
+                    .await?;
+                elem.clear().await?;
+                elem.send_keys(text).await?;
+            }
+            Action::Select(css, text) => {
+                let elem = root
+                    .query(By::Css(css))
+                    .wait(wait, interval)
+                    .first()
+                    .await?;
+                SelectElement::new(&elem)
+                    .await?
+                    .select_by_visible_text(text)
+                    .await?;
+            }
+            Action::WaitFor(css) => {
+                root.query(By::Css(css))
+                    .wait(wait, interval)
+                    .first()
+                    .await?;
+            }
+            Action::Js(js) => {
+                driver.execute(js, vec![]).await?;
+            }
+            Action::Sleep(ms) => tokio::time::sleep(Duration::from_millis(*ms)).await,
+        }
+        Ok(())
+    }
+}
+
+/// Timeouts, retries and delays for scraping a job source. Times are in milliseconds.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Timing {
+    /// How long to wait for a page to load.
+    pub page_load_ms: u64,
+    /// How long to wait for an element to appear, or for the next page after clicking.
+    pub wait_ms: u64,
+    /// How many times to retry a page that failed before giving up on the job source.
+    pub retries: u32,
+    /// How long to wait before the first retry. Doubles after every retry.
+    pub backoff_ms: u64,
+    /// How long to wait between pages.
+    pub page_delay_ms: u64,
+}
+
+impl Default for Timing {
+    fn default() -> Self {
+        Self {
+            page_load_ms: 30_000,
+            wait_ms: 8_000,
+            retries: 2,
+            backoff_ms: 1_000,
+            page_delay_ms: 0,
+        }
+    }
 }
 
 impl Display for JobSource {
@@ -60,76 +219,327 @@ impl Display for JobSource {
 }
 
 impl JobSource {
-    pub async fn scrape(&self, driver: &WebDriver) -> WebDriverResult<HashMap<String, Job>> {
+    const DEFAULT_MAX_PAGES: usize = 100;
+
+    /// Scrapes every page of jobs from every URL, returning the jobs and the number of pages
+    /// visited.
+    pub async fn scrape(
+        &self,
+        driver: &WebDriver,
+    ) -> WebDriverResult<(HashMap<String, Job>, usize)> {
         let mut jobs = HashMap::new();
+        let mut pages = 0;
+        driver
+            .set_page_load_timeout(Duration::from_millis(self.timing.page_load_ms))
+            .await?;
 
-        let mut url = self.url.clone();
-        for page in 0.. {
-            // Load the next page.
-            log::debug!("[{}] Page {}: {}", self.name, page, url);
-            driver.goto(url.as_str()).await?;
-            if let Some(css) = &self.wait_for {
-                log::debug!("[{}] Page {}: Waiting for {}", self.name, page, css);
-                driver.query(By::Css(css)).first().await?;
+        for (i, url) in self.entry_urls().into_iter().enumerate() {
+            // Be polite between entry URLs too, even if the last one had no jobs.
+            if i > 0 {
+                tokio::time::sleep(Duration::from_millis(self.timing.page_delay_ms)).await;
             }
+            pages += self.scrape_entry(driver, url, &mut jobs).await?;
+        }
 
-            // Find the root element.
-            let mut root = driver.query(By::Css("*")).nowait().first().await?;
-            if !self.sub_doms.is_empty() {
-                driver.enter_default_frame().await?;
-                for sub_dom in &self.sub_doms {
-                    root = sub_dom.enter(driver, &root).await?;
-                }
+        Ok((jobs, pages))
+    }
+
+    /// Returns every URL to start scraping from.
+    fn entry_urls(&self) -> Vec<Url> {
+        let urls = std::iter::once(&self.url).chain(&self.more_urls);
+        if self.query_variants.is_empty() {
+            return urls.cloned().collect();
+        }
+
+        urls.flat_map(|url| {
+            self.query_variants.iter().map(move |params| {
+                let mut url = url.clone();
+                let query = url
+                    .query_pairs()
+                    .filter(|(k, _)| !params.contains_key(k.as_ref()))
+                    .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                    .collect::<Vec<_>>();
+                url.query_pairs_mut()
+                    .clear()
+                    .extend_pairs(query)
+                    .extend_pairs(params);
+                url
+            })
+        })
+        .collect()
+    }
+
+    /// Scrapes every page of jobs starting from a URL, adding them to `jobs` and returning the
+    /// number of pages visited.
+    ///
+    /// Jobs that were already found on another page are skipped.
+    async fn scrape_entry(
+        &self,
+        driver: &WebDriver,
+        mut url: Url,
+        jobs: &mut HashMap<String, Job>,
+    ) -> WebDriverResult<usize> {
+        let mut pages = 0;
+        let max_pages = self.max_pages.unwrap_or(Self::DEFAULT_MAX_PAGES);
+        for page in 0..max_pages {
+            if page > 0 {
+                tokio::time::sleep(Duration::from_millis(self.timing.page_delay_ms)).await;
             }
-            let page_html = root.outer_html().await?;
 
-            // Parse jobs from page HTML.
+            // Scrape the page, retrying with backoff if it fails.
+            let mut retry = 0;
+            let (page_jobs, next_url) = loop {
+                match self.scrape_page(driver, &url, page).await {
+                    Ok(x) => break x,
+                    Err(e) if retry < self.timing.retries => {
+                        let backoff = self
+                            .timing
+                            .backoff_ms
+                            .saturating_mul(2u64.saturating_pow(retry));
+                        log::warn!(
+                            "[{}] Page {}: Retrying in {}ms: {}",
+                            self.name,
+                            page,
+                            backoff,
+                            e,
+                        );
+                        tokio::time::sleep(Duration::from_millis(backoff)).await;
+                        retry += 1;
+                    }
+                    Err(e) => return Err(e),
+                }
+            };
+            pages += 1;
+
             let prev_num_jobs = jobs.len();
-            jobs.extend(self.parse_page(&page_html));
+            let mut duplicates = 0;
+            for (id, job) in page_jobs {
+                match jobs.entry(id) {
+                    Entry::Occupied(_) => duplicates += 1,
+                    Entry::Vacant(entry) => {
+                        entry.insert(job);
+                    }
+                }
+            }
             log::debug!(
-                "[{}] Page {}: Found {} jobs ({} total)",
+                "[{}] Page {}: Found {} jobs ({} total, {} duplicates skipped)",
                 self.name,
                 page,
                 jobs.len() - prev_num_jobs,
                 jobs.len(),
+                duplicates,
+            );
+
+            // A URL template keeps going past the last page, so stop when there's nothing new.
+            bq!(
+                !matches!(self.pagination, Some(Pagination::UrlTemplate { .. }))
+                    || jobs.len() > prev_num_jobs
             );
+            url = bq!(next_url);
+            if page + 1 == max_pages {
+                log::warn!("[{}] Stopped at max pages: {}", self.name, max_pages);
+            }
+        }
 
-            // Go to the next page.
-            let next_page = bq!(self.next_page.as_ref());
-            if let Some(css) = &self.close_popup {
-                if let Ok(elem) = root.query(By::Css(css)).nowait().first().await {
-                    if let Ok(true) = elem.is_clickable().await {
-                        // This is `next_page.scroll_into_view()` but with instant scrolling.
-                        driver.execute(r#"arguments[0].scrollIntoView({block: "center", inline: "center", behavior: "instant"});"#, vec![elem.to_json()?]).await?;
-                        elem.click().await?;
+        Ok(pages)
+    }
+
+    /// Scrapes the jobs from one page, returning the jobs and the URL of the next page, if any.
+    async fn scrape_page(
+        &self,
+        driver: &WebDriver,
+        url: &Url,
+        page: usize,
+    ) -> WebDriverResult<(HashMap<String, Job>, Option<Url>)> {
+        let wait = Duration::from_millis(self.timing.wait_ms);
+        let interval = Duration::from_millis(100);
+
+        // Load the page.
+        log::debug!("[{}] Page {}: {}", self.name, page, url);
+        driver.goto(url.as_str()).await?;
+        // Relative job URLs are relative to where the page ended up.
+        let page_url = driver.current_url().await?;
+        if page == 0 {
+            for action in &self.actions {
+                log::debug!("[{}] Page {}: {:?}", self.name, page, action);
+                driver.enter_default_frame().await?;
+                let root = driver.query(By::Css("*")).nowait().first().await?;
+                action.run(driver, &root, wait).await?;
+            }
+            for action in &self.sub_dom_actions {
+                log::debug!("[{}] Page {}: {:?} in sub-DOMs", self.name, page, action);
+                let root = self.root(driver).await?;
+                action.run(driver, &root, wait).await?;
+            }
+        }
+        if let Some(css) = &self.wait_for {
+            log::debug!("[{}] Page {}: Waiting for {}", self.name, page, css);
+            driver
+                .query(By::Css(css))
+                .wait(wait, interval)
+                .first()
+                .await?;
+        }
+
+        // Load more jobs into the page, then parse jobs from page HTML.
+        if matches!(
+            self.pagination,
+            Some(Pagination::LoadMore(_) | Pagination::Scroll),
+        ) {
+            self.load_more(driver, &page_url, page).await?;
+        }
+        let root = self.root(driver).await?;
+        let jobs = self.parse_page(&page_url, &root.outer_html().await?);
+
+        // Find the next page.
+        let next_url = match self.pagination() {
+            Some(Pagination::NextButton(css)) => {
+                self.close_popup(driver, &root).await?;
+                let Ok(next_page) = root.query(By::Css(css)).nowait().first().await else {
+                    return Ok((jobs, None));
+                };
+                log::debug!("[{}] Page {}: Next page...", self.name, page);
+                next_page
+                    .wait_until()
+                    .wait(wait, interval)
+                    .clickable()
+                    .await?;
+                scroll_into_view(driver, &next_page).await?;
+                // The page may have redirected from `url`, so wait for the URL to change from here.
+                let old_url = driver.current_url().await?;
+                next_page.click().await?;
+                let start = Instant::now();
+                loop {
+                    tokio::time::sleep(interval).await;
+                    let next_url = driver.current_url().await?;
+                    if next_url != old_url {
+                        break next_url;
+                    }
+                    if start.elapsed() >= wait {
+                        return Err(WebDriverError::Timeout("waiting for next page".to_string()));
                     }
                 }
             }
-            let next_page = bq!(root.query(By::Css(next_page)).nowait().first().await);
-            log::debug!("[{}] Page {}: Next page...", self.name, page);
-            let old_url = driver.current_url().await?;
-            next_page.wait_until().clickable().await?;
-            // This is `next_page.scroll_into_view()` but with instant scrolling.
-            driver.execute(r#"arguments[0].scrollIntoView({block: "center", inline: "center", behavior: "instant"});"#, vec![next_page.to_json()?]).await?;
-            next_page.click().await?;
-            for i in 0..80 {
-                tokio::time::sleep(Duration::from_millis(100)).await;
-                url = driver.current_url().await?;
-                if url != old_url {
-                    break;
+            Some(Pagination::NextLink(css)) => {
+                let Ok(next_link) = root.query(By::Css(css)).nowait().first().await else {
+                    return Ok((jobs, None));
+                };
+                let Some(href) = next_link.attr("href").await? else {
+                    return Ok((jobs, None));
+                };
+                let Ok(next_url) = url.join(&href) else {
+                    return Ok((jobs, None));
+                };
+                next_url
+            }
+            Some(Pagination::UrlTemplate {
+                template,
+                start,
+                step,
+            }) => {
+                let n = start + (page + 1) * step;
+                let Ok(next_url) = Url::parse(&template.replace("{page}", &n.to_string())) else {
+                    return Ok((jobs, None));
+                };
+                next_url
+            }
+            Some(Pagination::LoadMore(_) | Pagination::Scroll) | None => {
+                return Ok((jobs, None));
+            }
+        };
+
+        // Don't go in circles.
+        Ok((jobs, (&next_url != url).then_some(next_url)))
+    }
+
+    /// Clicks "Load more" or scrolls to the bottom of the page until the number of jobs stops
+    /// growing.
+    async fn load_more(
+        &self,
+        driver: &WebDriver,
+        page_url: &Url,
+        page: usize,
+    ) -> WebDriverResult<()> {
+        let wait = Duration::from_millis(self.timing.wait_ms);
+        let interval = Duration::from_millis(100);
+        let max_pages = self.max_pages.unwrap_or(Self::DEFAULT_MAX_PAGES);
+
+        let mut root = self.root(driver).await?;
+        let mut num_jobs = self.parse_page(page_url, &root.outer_html().await?).len();
+        for i in 1..max_pages {
+            match &self.pagination {
+                Some(Pagination::LoadMore(css)) => {
+                    self.close_popup(driver, &root).await?;
+                    let Ok(button) = root.query(By::Css(css)).nowait().first().await else {
+                        break;
+                    };
+                    if !button.is_clickable().await? {
+                        break;
+                    }
+                    scroll_into_view(driver, &button).await?;
+                    button.click().await?;
                 }
-                if i == 79 {
-                    return Err(WebDriverError::Timeout("waiting for next page".to_string()));
+                Some(Pagination::Scroll) => {
+                    driver
+                        .execute("window.scrollTo(0, document.body.scrollHeight);", vec![])
+                        .await?;
                 }
+                _ => break,
             }
+            log::debug!("[{}] Page {}: Loading more ({})...", self.name, page, i);
+
+            // Wait for more jobs to show up.
+            let start = Instant::now();
+            let prev_num_jobs = num_jobs;
+            while num_jobs == prev_num_jobs && start.elapsed() < wait {
+                tokio::time::sleep(interval).await;
+                root = self.root(driver).await?;
+                num_jobs = self.parse_page(page_url, &root.outer_html().await?).len();
+            }
+            bq!(num_jobs > prev_num_jobs);
         }
 
-        Ok(jobs)
+        Ok(())
+    }
+
+    /// Returns the pagination, including the `next_page` shorthand.
+    fn pagination(&self) -> Option<Pagination> {
+        self.pagination.clone().or_else(|| {
+            self.next_page
+                .as_ref()
+                .map(|css| Pagination::NextButton(css.clone()))
+        })
+    }
+
+    /// Closes the popup, if there is one, so it doesn't get in the way of a click.
+    async fn close_popup(&self, driver: &WebDriver, root: &WebElement) -> WebDriverResult<()> {
+        let Some(css) = &self.close_popup else {
+            return Ok(());
+        };
+        if let Ok(elem) = root.query(By::Css(css)).nowait().first().await {
+            if let Ok(true) = elem.is_clickable().await {
+                scroll_into_view(driver, &elem).await?;
+                elem.click().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Finds the root element of the current page, inside the sub-DOMs.
+    ///
+    /// This starts from the top-level document, so it can be called again after entering a frame.
+    pub(crate) async fn root(&self, driver: &WebDriver) -> WebDriverResult<WebElement> {
+        driver.enter_default_frame().await?;
+        let mut root = driver.query(By::Css("*")).nowait().first().await?;
+        for sub_dom in &self.sub_doms {
+            root = sub_dom.enter(driver, &root).await?;
+        }
+        Ok(root)
     }
 
     // TODO: Return `Result`.
-    /// Extracts a collection of jobs from HTML.
-    fn parse_page(&self, page_html: &str) -> HashMap<String, Job> {
+    /// Extracts a collection of jobs from the HTML of the page at `page_url`.
+    pub(crate) fn parse_page(&self, page_url: &Url, page_html: &str) -> HashMap<String, Job> {
         let mut jobs = HashMap::new();
 
         // Determine the slice of HTML that contains the list of jobs.
@@ -170,7 +580,7 @@ impl JobSource {
                 let url = cq!(url_re.captures(job_html));
                 let url = c!(url.get(1)).as_str();
                 let url = decode_html_entities(url);
-                let url = c!(self.url.join(&url));
+                let url = c!(page_url.join(&url));
                 url
             } else {
                 self.url.clone()
@@ -186,8 +596,10 @@ impl JobSource {
             } else {
                 url.to_string()
             };
+            // Keep the first job with each ID.
             if jobs.contains_key(&id) {
-                log::warn!("Job found with duplicate ID: {}", id);
+                log::debug!("[{}] Skipping job with duplicate ID: {}", self.name, id);
+                continue;
             }
 
             // Save the job by its ID.
@@ -196,6 +608,299 @@ impl JobSource {
 
         jobs
     }
+
+    /// Checks for mistakes that would otherwise only show up while scraping.
+    pub fn validate(&self) -> Vec<SourceProblem> {
+        let mut problems = vec![];
+        let mut problem = |field: &str, message: String| {
+            problems.push(SourceProblem {
+                source: self.name.clone(),
+                field: Some(field.to_string()),
+                message,
+            });
+        };
+
+        if !matches!(self.url.scheme(), "http" | "https") {
+            problem("url", format!("{} is not an http(s) URL", self.url));
+        }
+        for url in &self.more_urls {
+            if !matches!(url.scheme(), "http" | "https") {
+                problem("more_urls", format!("{} is not an http(s) URL", url));
+            }
+        }
+
+        // These regexes are expected to capture their value in group 1.
+        for (field, re) in [
+            ("job_company_re", self.job_company_re.as_ref()),
+            ("job_id_re", self.job_id_re.as_ref()),
+            ("job_url_re", self.job_url_re.as_ref()),
+            ("job_title_re", Some(&self.job_title_re)),
+        ] {
+            if re.is_some_and(|x| x.captures_len() < 2) {
+                problem(field, "has no capture group".to_string());
+            }
+        }
+
+        let sub_doms = self.sub_doms.iter().map(|x| match x {
+            SubDom::Frame(css) | SubDom::Shadow(css) => ("sub_doms", css),
+        });
+        let actions = self
+            .actions
+            .iter()
+            .map(|x| ("actions", x))
+            .chain(self.sub_dom_actions.iter().map(|x| ("sub_dom_actions", x)))
+            .filter_map(|(field, x)| match x {
+                Action::Click(css)
+                | Action::Type(css, _)
+                | Action::Select(css, _)
+                | Action::WaitFor(css) => Some((field, css)),
+                Action::Js(_) | Action::Sleep(_) => None,
+            });
+        let pagination_css = match &self.pagination {
+            Some(
+                Pagination::NextButton(css) | Pagination::NextLink(css) | Pagination::LoadMore(css),
+            ) => Some(css),
+            _ => None,
+        };
+        for (field, css) in [
+            ("wait_for", self.wait_for.as_ref()),
+            ("close_popup", self.close_popup.as_ref()),
+            ("next_page", self.next_page.as_ref()),
+            ("pagination", pagination_css),
+        ]
+        .into_iter()
+        .filter_map(|(field, css)| Some((field, css?)))
+        .chain(sub_doms)
+        .chain(actions)
+        {
+            if let Err(e) = scraper::Selector::parse(css) {
+                problem(field, format!("invalid CSS selector {:?}: {}", css, e));
+            }
+        }
+
+        if !self.sub_dom_actions.is_empty() && self.sub_doms.is_empty() {
+            problem(
+                "sub_dom_actions",
+                "can't be used without sub_doms".to_string(),
+            );
+        }
+        if self.next_page.is_some() && self.pagination.is_some() {
+            problem("next_page", "can't be used with pagination".to_string());
+        }
+        if let Some(Pagination::UrlTemplate {
+            template, start, ..
+        }) = &self.pagination
+        {
+            if !self.more_urls.is_empty() || !self.query_variants.is_empty() {
+                problem(
+                    "pagination",
+                    "can't use a URL template with more than one URL".to_string(),
+                );
+            }
+            if !template.contains("{page}") {
+                problem("pagination", format!("{:?} has no {{page}}", template));
+            } else if let Err(e) = Url::parse(&template.replace("{page}", &start.to_string())) {
+                problem("pagination", format!("{:?} is not a URL: {}", template, e));
+            }
+        }
+        if self.max_pages == Some(0) {
+            problem("max_pages", "must be at least 1".to_string());
+        }
+
+        problems
+    }
+}
+
+/// This is `elem.scroll_into_view()` but with instant scrolling.
+async fn scroll_into_view(driver: &WebDriver, elem: &WebElement) -> WebDriverResult<()> {
+    driver.execute(r#"arguments[0].scrollIntoView({block: "center", inline: "center", behavior: "instant"});"#, vec![elem.to_json()?]).await?;
+    Ok(())
+}
+
+/// Parses a RON list of job sources one entry at a time, so that a broken entry doesn't hide
+/// the problems in the others.
+pub fn parse_job_sources(s: &str) -> (Vec<JobSource>, Vec<SourceProblem>) {
+    re!(NAME_RE, r#"\bname\s*:\s*"([^"]*)""#);
+    re!(FIELD_RE, r"(?m)^\s*(\w+)\s*:");
+
+    let (entries, syntax_error) = split_entries(s);
+    let num_entries = entries.len();
+    let mut job_sources = vec![];
+    let mut problems = vec![];
+    for (i, (line, entry)) in entries.into_iter().enumerate() {
+        let e = match ron::from_str::<JobSource>(entry) {
+            Ok(job_source) => {
+                job_sources.push(job_source);
+                continue;
+            }
+            Err(e) => e,
+        };
+
+        let field = if let ron::Error::MissingStructField { field, .. } = &e.code {
+            Some(field.to_string())
+        } else {
+            // Guess the field from the last field name before the error.
+            let offset = entry
+                .split_inclusive('\n')
+                .take(e.position.line.saturating_sub(1))
+                .map(str::len)
+                .sum::<usize>()
+                + e.position.col.saturating_sub(1);
+            let end = entry
+                .char_indices()
+                .map(|(i, _)| i)
+                .chain([entry.len()])
+                .take_while(|&i| i <= offset)
+                .last()
+                .unwrap_or_default();
+            FIELD_RE
+                .captures_iter(&entry[..end])
+                .last()
+                .map(|x| x[1].to_string())
+        };
+
+        problems.push(SourceProblem {
+            source: NAME_RE
+                .captures(entry)
+                .map(|x| x[1].to_string())
+                .unwrap_or_else(|| format!("#{}", i)),
+            field,
+            message: format!("line {}: {}", line + e.position.line - 1, e.code),
+        });
+    }
+
+    // The entries after a syntax error can't be told apart.
+    if let Some(e) = syntax_error {
+        problems.push(SourceProblem {
+            source: format!("#{}", num_entries),
+            field: None,
+            message: format!("line {}: {} (skipping the rest)", e.position.line, e.code),
+        });
+    }
+
+    (job_sources, problems)
+}
+
+/// Splits a RON list into the text of each entry, along with the line it starts on.
+///
+/// Stops at the first syntax error, returning it along with the entries before it.
+fn split_entries(s: &str) -> (Vec<(usize, &str)>, Option<SpannedError>) {
+    let mut entries = vec![];
+    let mut split = || -> Result<(), SpannedError> {
+        let mut i = skip_whitespace(s, 0)?;
+        if !s[i..].starts_with('[') {
+            return Err(error_at(s, i, ron::Error::ExpectedArray));
+        }
+        i += 1;
+
+        loop {
+            i = skip_whitespace(s, i)?;
+            if s[i..].starts_with(']') {
+                return Ok(());
+            }
+
+            // Let ron find the end of the entry, without deserializing it yet.
+            let mut de = Deserializer::from_str(&s[i..]).map_err(|e| offset_error(s, i, e))?;
+            if let Err(e) = IgnoredAny::deserialize(&mut de) {
+                return Err(offset_error(s, i, de.span_error(e)));
+            }
+            let end = s.len() - de.remainder().len();
+            entries.push((s[..i].matches('\n').count() + 1, &s[i..end]));
+
+            i = skip_whitespace(s, end)?;
+            if s[i..].starts_with(',') {
+                i += 1;
+            } else if !s[i..].starts_with(']') {
+                return Err(error_at(s, i, ron::Error::ExpectedArrayEnd));
+            }
+        }
+    };
+    let error = split().err();
+
+    (entries, error)
+}
+
+/// Returns the offset of the next token in `s` at or after `offset`, skipping comments.
+fn skip_whitespace(s: &str, offset: usize) -> Result<usize, SpannedError> {
+    let de = Deserializer::from_str(&s[offset..]).map_err(|e| offset_error(s, offset, e))?;
+    Ok(s.len() - de.remainder().len())
+}
+
+/// Returns an error at an offset in `s`.
+fn error_at(s: &str, offset: usize, code: ron::Error) -> SpannedError {
+    let position = Position { line: 1, col: 1 };
+    offset_error(s, offset, SpannedError { code, position })
+}
+
+/// Moves the position of an error in `s[offset..]` to its position in `s`.
+fn offset_error(s: &str, offset: usize, mut e: SpannedError) -> SpannedError {
+    let before = &s[..offset];
+    if e.position.line == 1 {
+        e.position.col += before.len() - before.rfind('\n').map_or(0, |x| x + 1);
+    }
+    e.position.line += before.matches('\n').count();
+    e
+}
+
+/// A problem found while validating a job source.
+#[derive(Debug)]
+pub struct SourceProblem {
+    /// The name of the job source.
+    pub source: String,
+    /// The field with the problem, if known.
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl Display for SourceProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "[{}] {}: {}", self.source, field, self.message),
+            None => write!(f, "[{}] {}", self.source, self.message),
+        }
+    }
+}
+
+/// A selection of job sources by name, where names can be glob patterns (case-insensitive).
+#[derive(Default, Debug)]
+pub struct SourceSelection {
+    /// Only select the job sources matching one of these patterns, or all of them if empty.
+    pub only: Vec<Pattern>,
+    /// Skip the job sources matching one of these patterns.
+    pub skip: Vec<Pattern>,
+}
+
+impl SourceSelection {
+    const MATCH_OPTIONS: MatchOptions = MatchOptions {
+        case_sensitive: false,
+        require_literal_separator: false,
+        require_literal_leading_dot: false,
+    };
+
+    pub fn matches(&self, name: &str) -> bool {
+        (self.only.is_empty()
+            || self
+                .only
+                .iter()
+                .any(|x| x.matches_with(name, Self::MATCH_OPTIONS)))
+            && !self
+                .skip
+                .iter()
+                .any(|x| x.matches_with(name, Self::MATCH_OPTIONS))
+    }
+
+    /// Returns the patterns that don't match any of the given names.
+    pub fn unmatched<'a>(&'a self, names: &[&str]) -> Vec<&'a Pattern> {
+        self.only
+            .iter()
+            .chain(&self.skip)
+            .filter(|x| {
+                !names
+                    .iter()
+                    .any(|name| x.matches_with(name, Self::MATCH_OPTIONS))
+            })
+            .collect()
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -227,3 +932,202 @@ impl SubDom {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JOB_SOURCES: &str = r##"[
+    // A comment with a (paren.
+    (
+        name: "Good",
+        url: "https://example.com/jobs",
+        next_job_re: r#"<li class="(job)">"#,
+        job_title_re: "<h3>([^<]*)</h3>",
+    ),
+    JobSource(
+        name: "Bad regex",
+        url: "https://example.com/jobs",
+        next_job_re: "<li>",
+        job_title_re: "<h3>([^<]*</h3>",
+    ),
+    (
+        name: "Bad fields",
+        url: "ftp://example.com/jobs",
+        wait_for: Some("ul >"),
+        actions: [Click("#accept-cookies"), Type("form >", "engineer"), Sleep(500)],
+        sub_dom_actions: [WaitFor("ul")],
+        timing: (retries: 5),
+        pagination: Some(UrlTemplate(template: "https://example.com/jobs", start: 1, step: 1)),
+        next_job_re: "<li>",
+        job_url_re: Some("href=\"[^\"]*\""),
+        job_title_re: "<h3>([^<]*)</h3>",
+    ),
+]"##;
+
+    #[test]
+    fn parse() {
+        let (job_sources, problems) = parse_job_sources(JOB_SOURCES);
+        assert_eq!(job_sources.len(), 2);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].source, "Bad regex");
+        assert_eq!(problems[0].field.as_deref(), Some("job_title_re"));
+        assert!(
+            problems[0].message.starts_with("line 13:"),
+            "{}",
+            problems[0]
+        );
+
+        // Missing timing fields fall back to the defaults.
+        assert_eq!(job_sources[1].timing.retries, 5);
+        assert_eq!(job_sources[1].timing.wait_ms, Timing::default().wait_ms);
+    }
+
+    #[test]
+    fn parse_tricky() {
+        let (job_sources, problems) = parse_job_sources(
+            r#"[
+    (
+        name: "Brackets ]) in strings",
+        url: "https://example.com/jobs",
+        next_job_re: "<li class=\"[a-z]+\">",
+        job_title_re: r"<h3>\(([^<]*)\)</h3>",
+    ),
+    (
+        name: "Café Société",
+        url: "https://example.com/jobs",
+        next_job_re: "<li>",
+        job_title_re: "<h3>Ünïcödé ([^<]*</h3>",
+    ),
+    (
+        name: "Broken syntax",
+        url: "https://example.com/jobs"
+        next_job_re: "<li>",
+    ),
+    (
+        name: "Hidden by the syntax error",
+    ),
+]"#,
+        );
+        assert_eq!(job_sources.len(), 1);
+        assert_eq!(job_sources[0].name, "Brackets ]) in strings");
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].source, "Café Société");
+        assert_eq!(problems[0].field.as_deref(), Some("job_title_re"));
+        assert_eq!(problems[1].source, "#2");
+        assert!(
+            problems[1].message.starts_with("line 17:"),
+            "{}",
+            problems[1]
+        );
+    }
+
+    #[test]
+    fn validate() {
+        let (job_sources, _) = parse_job_sources(JOB_SOURCES);
+        assert!(job_sources[0].validate().is_empty());
+        let fields = job_sources[1]
+            .validate()
+            .into_iter()
+            .map(|x| x.field.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            [
+                "url",
+                "job_url_re",
+                "wait_for",
+                "actions",
+                "sub_dom_actions",
+                "pagination",
+            ],
+        );
+    }
+
+    #[test]
+    fn parse_page_relative_urls() {
+        let (job_sources, _) = parse_job_sources(
+            r#"[(
+                name: "Relative",
+                url: "https://example.com/jobs",
+                next_job_re: "<li>",
+                job_url_re: Some("href=\"([^\"]*)\""),
+                job_title_re: "<h3>([^<]*)</h3>",
+            )]"#,
+        );
+        let page_url = Url::parse("https://studio.example.com/careers/page/2").unwrap();
+        let jobs = job_sources[0].parse_page(
+            &page_url,
+            r#"<ul><li><a href="openings/1"><h3>Programmer</h3></a></li></ul>"#,
+        );
+        let urls = jobs.keys().collect::<Vec<_>>();
+        assert_eq!(urls, ["https://studio.example.com/careers/page/openings/1"]);
+    }
+
+    #[test]
+    fn entry_urls() {
+        let (job_sources, _) = parse_job_sources(
+            r#"[(
+                name: "Variants",
+                url: "https://example.com/jobs?team=art&page=1",
+                more_urls: ["https://example.com/studio/jobs"],
+                query_variants: [{"team": "engineering"}, {"team": "technology"}],
+                next_job_re: "<li>",
+                job_title_re: "<h3>([^<]*)</h3>",
+            )]"#,
+        );
+        let urls = job_sources[0]
+            .entry_urls()
+            .into_iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/jobs?page=1&team=engineering",
+                "https://example.com/jobs?page=1&team=technology",
+                "https://example.com/studio/jobs?team=engineering",
+                "https://example.com/studio/jobs?team=technology",
+            ],
+        );
+    }
+
+    /// Needs a WebDriver server binary and its browser, so run it with `cargo test -- --ignored`.
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore]
+    async fn root_in_frame() {
+        let data_dir = std::env::temp_dir().join("find_a_job_root_in_frame");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let page = data_dir.join("page.html");
+        std::fs::write(
+            &page,
+            "<p>Outside</p><iframe srcdoc=\"<ul><li>Programmer</li></ul>\"></iframe>",
+        )
+        .unwrap();
+        let (job_sources, _) = parse_job_sources(
+            r#"[(
+                name: "Frame",
+                url: "https://example.com/jobs",
+                sub_doms: [Frame("iframe")],
+                next_job_re: "<li>",
+                job_title_re: "([^<]*)</li>",
+            )]"#,
+        );
+
+        let mut bot = crate::bot::Bot::with_data_dir(&data_dir);
+        bot.init().await.unwrap();
+        let driver = bot.driver.as_ref().unwrap();
+        driver
+            .goto(Url::from_file_path(&page).unwrap().as_str())
+            .await
+            .unwrap();
+        // The driver is inside the frame after the first call.
+        for _ in 0..2 {
+            let root = job_sources[0].root(driver).await.unwrap();
+            let jobs =
+                job_sources[0].parse_page(&job_sources[0].url, &root.outer_html().await.unwrap());
+            assert_eq!(jobs.len(), 1);
+        }
+        bot.quit().await.unwrap();
+    }
+}
@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A record of the job sources finished so far in a scrape run, so an interrupted run can be
+/// resumed.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct RunJournal {
+    pub started: DateTime<Utc>,
+    /// The names of the job sources that have been scraped and saved.
+    pub finished: Vec<String>,
+    /// The IDs of the new jobs found so far, to send notifications about when the run is done.
+    #[serde(default)]
+    pub new_ids: Vec<String>,
+}
+
+impl Default for RunJournal {
+    fn default() -> Self {
+        Self {
+            started: Utc::now(),
+            finished: vec![],
+            new_ids: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let journal = RunJournal {
+            finished: vec!["Acme".to_string(), "Initech".to_string()],
+            new_ids: vec!["Acme/123".to_string()],
+            ..Default::default()
+        };
+        let journal_str = ron::ser::to_string_pretty(&journal, Default::default()).unwrap();
+        let loaded: RunJournal = ron::from_str(&journal_str).unwrap();
+        assert_eq!(loaded.started, journal.started);
+        assert_eq!(loaded.finished, journal.finished);
+        assert_eq!(loaded.new_ids, journal.new_ids);
+    }
+}
@@ -1,9 +1,50 @@
+/// Defines a lazily compiled static regex.
+macro_rules! re {
+    ($name:ident, $($e:expr),* $(,)?) => {
+        static $name: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(
+            || regex::Regex::new(concat!($($e),*)).unwrap(),
+        );
+    };
+}
+
+mod author;
 mod bot;
+mod browser;
+mod export;
+mod feed;
+mod filter;
+mod health;
+mod infer;
 mod job;
 mod job_source;
+mod journal;
+mod notify;
+mod profile;
+mod report;
+#[cfg(feature = "server")]
+mod server;
+mod summary;
+#[cfg(feature = "tui")]
+mod tui;
+mod webhook;
 
+pub use author::SourceAuthor;
 pub use bot::Bot;
-pub use job::{Job, JobDiscipline, JobLevel, JobSpecialty};
+pub use browser::{Browser, BrowserConfig};
+pub use export::ExportFormat;
+pub use feed::Feed;
+pub use filter::{JobFilter, JobSort};
+pub use health::{Anomaly, HealthConfig, SourceHealth, SourceRun};
+pub use job::{Job, JobDiscipline, JobLevel, JobSpecialty, ScoreConfig};
+pub use job_source::{SourceProblem, SourceSelection};
+pub use notify::{Digest, EmailConfig, NotifyConfig, SmtpTls};
+pub use profile::{JobState, JobStatus, Profile};
+#[cfg(feature = "server")]
+pub use server::serve;
+pub use summary::{ScrapeSummary, SourceSummary};
+#[cfg(feature = "tui")]
+pub use tui::run_tui;
+pub use webhook::{NotifyEvent, WebhookConfig, WebhookFormat};
 
 pub fn init_logger(default_level: log::LevelFilter) {
     pretty_env_logger::formatted_timed_builder()
@@ -0,0 +1,674 @@
+use std::{collections::HashMap, error::Error, future::Future, path::PathBuf};
+
+use chrono::{Local, NaiveDate, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use find_a_job::{
+    init_logger, Bot, Browser, BrowserConfig, ExportFormat, Feed, HealthConfig, Job, JobDiscipline,
+    JobFilter, JobLevel, JobSort, JobSpecialty, JobStatus, Profile, SourceAuthor, SourceSelection,
+};
+use glob::Pattern;
+use regex::Regex;
+use thirtyfour::error::WebDriverResult;
+use url::Url;
+
+/// Scrape job sources for jobs, then rank and track them.
+#[derive(Parser)]
+struct Cli {
+    /// The directory containing the job database, job sources and profiles.
+    #[arg(long, global = true, default_value = Bot::DEFAULT_DATA_DIR)]
+    data_dir: PathBuf,
+    /// The log level [default: debug for scrape, info otherwise].
+    #[arg(long, global = true)]
+    log_level: Option<log::LevelFilter>,
+    /// Show the browser window instead of running it headless.
+    #[arg(long, global = true)]
+    headful: bool,
+    /// The profile to rank and track jobs for.
+    #[arg(
+        short,
+        long,
+        global = true,
+        default_value = Profile::DEFAULT_NAME,
+        value_parser = Profile::parse_name,
+    )]
+    profile: String,
+    /// The browser to scrape with.
+    #[arg(long, global = true, value_enum, default_value_t)]
+    browser: Browser,
+    /// The WebDriver server binary [default: geckodriver or chromedriver].
+    #[arg(long, global = true)]
+    driver_path: Option<PathBuf>,
+    /// The port for the WebDriver server to listen on.
+    #[arg(long, global = true, default_value_t = BrowserConfig::default().port)]
+    port: u16,
+    /// Connect to an existing WebDriver server instead of spawning one.
+    #[arg(long, global = true)]
+    remote_url: Option<Url>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Scrape job sources for new jobs.
+    Scrape {
+        /// The names of the job sources to scrape, or glob patterns [default: all].
+        sources: Vec<Pattern>,
+        /// Skip the job sources with these names or glob patterns.
+        #[arg(short, long, value_delimiter = ',')]
+        skip: Vec<Pattern>,
+        /// Don't mark jobs missing if a job source loses more than this percentage of its jobs.
+        #[arg(long, default_value_t = HealthConfig::default().max_drop_percent)]
+        max_drop: usize,
+        /// Skip the job sources that finished in the last interrupted run.
+        #[arg(long)]
+        resume: bool,
+        /// Warn when a job source fails this many times in a row.
+        #[arg(long, default_value_t = HealthConfig::default().max_failures)]
+        max_failures: usize,
+    },
+    /// List the open jobs, ranked for the profile.
+    List {
+        #[command(flatten)]
+        filter: FilterArgs,
+    },
+    /// Re-parse jobs from their titles. Useful when parsing logic changes.
+    #[command(alias = "reparse")]
+    Fix,
+    /// Show how a job is scored for the profile.
+    Explain {
+        /// The job's ID or URL.
+        job: String,
+    },
+    /// Export jobs as RON, CSV, JSON, JSON Lines, a Markdown table, or an HTML report.
+    Export {
+        #[command(flatten)]
+        filter: FilterArgs,
+        /// The format to export in.
+        #[arg(short, long, value_enum, default_value_t)]
+        format: ExportFormat,
+        /// The file to write to [default: stdout].
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Export jobs as an Atom feed, newest first.
+    ///
+    /// The feed is always sorted by age, so `--limit` keeps the newest jobs.
+    Feed {
+        #[command(flatten)]
+        filter: FilterArgs,
+        /// The title of the feed.
+        #[arg(long, default_value = "find_a_job")]
+        feed_title: String,
+        /// The file to write to [default: stdout].
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// List the job sources.
+    Sources,
+    /// Check the job sources for mistakes without scraping them.
+    Validate,
+    /// Write a new job source interactively against a page.
+    Author {
+        /// The URL to open in the browser, or a saved HTML file.
+        target: String,
+    },
+    /// Propose a new job source from the repeated job cards in a page.
+    Infer {
+        /// The URL to open in the browser, or a saved HTML file.
+        target: String,
+        /// Continue editing the proposed job source interactively.
+        #[arg(short, long)]
+        edit: bool,
+    },
+    /// Send notifications about recently seen jobs, e.g. to test the notification settings.
+    Notify {
+        /// Include the jobs first seen within this many days.
+        #[arg(short, long, default_value_t = 1)]
+        days: i64,
+    },
+    /// Serve a web UI on localhost for browsing and triaging jobs.
+    #[cfg(feature = "server")]
+    Serve {
+        /// The port to listen on.
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+    },
+    /// Browse and triage jobs in a full-screen terminal UI.
+    ///
+    /// The "Recently removed" view only lists jobs removed since this version, as older removals
+    /// weren't kept.
+    #[cfg(feature = "tui")]
+    Tui,
+    /// Track the application state of a job, or hide it from the job list.
+    Track {
+        #[command(subcommand)]
+        command: TrackCommand,
+    },
+    /// Hide every job from a company or job source.
+    Mute { kind: MuteKind, name: String },
+    /// Stop hiding every job from a company or job source.
+    Unmute { kind: MuteKind, name: String },
+}
+
+#[derive(Args)]
+struct FilterArgs {
+    /// Only include jobs with these statuses, including missing and removed jobs.
+    #[arg(short, long, value_enum, value_delimiter = ',')]
+    status: Vec<JobStatus>,
+    /// Include jobs that are hidden, snoozed, or from a muted company or source.
+    #[arg(short = 'a', long)]
+    show_hidden: bool,
+    /// Include jobs that are missing from their job source.
+    #[arg(short = 'm', long)]
+    include_missing: bool,
+    /// Only include jobs with these levels.
+    #[arg(short, long, value_enum, value_delimiter = ',')]
+    level: Vec<JobLevel>,
+    /// Only include jobs with these disciplines.
+    #[arg(short, long, value_enum, value_delimiter = ',')]
+    discipline: Vec<JobDiscipline>,
+    /// Only include jobs with these specialties.
+    #[arg(long, value_enum, value_delimiter = ',')]
+    specialty: Vec<JobSpecialty>,
+    /// Only include jobs from a company containing one of these names.
+    #[arg(short, long, value_delimiter = ',')]
+    company: Vec<String>,
+    /// Only include jobs from these job sources.
+    #[arg(long, value_delimiter = ',')]
+    source: Vec<String>,
+    /// Only include jobs with at least this score.
+    #[arg(long, allow_negative_numbers = true)]
+    min_score: Option<i32>,
+    /// Only include jobs first seen at least this many days ago.
+    #[arg(long)]
+    min_age: Option<i64>,
+    /// Only include jobs first seen at most this many days ago.
+    #[arg(long)]
+    max_age: Option<i64>,
+    /// Only include jobs with a title matching this regex.
+    #[arg(short, long)]
+    title: Option<Regex>,
+    /// The order to list jobs in, from least to most relevant.
+    #[arg(long, value_enum, default_value_t)]
+    sort: JobSort,
+    /// Reverse the order to list jobs in.
+    #[arg(short, long)]
+    reverse: bool,
+    /// Only include the last N jobs in the order.
+    #[arg(short = 'n', long)]
+    limit: Option<usize>,
+}
+
+impl From<FilterArgs> for JobFilter {
+    fn from(args: FilterArgs) -> Self {
+        Self {
+            statuses: args.status,
+            show_hidden: args.show_hidden,
+            include_missing: args.include_missing,
+            levels: args.level,
+            disciplines: args.discipline,
+            specialties: args.specialty,
+            companies: args.company,
+            sources: args.source,
+            min_score: args.min_score,
+            min_age: args.min_age,
+            max_age: args.max_age,
+            title_re: args.title,
+            sort: args.sort,
+            reverse: args.reverse,
+            limit: args.limit,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum TrackCommand {
+    /// Move a job to a new status.
+    Status {
+        /// The job's ID or URL.
+        job: String,
+        status: JobStatus,
+        /// The date the status changed [default: today].
+        #[arg(short, long)]
+        date: Option<NaiveDate>,
+    },
+    /// Stop tracking the status of a job.
+    Untrack {
+        /// The job's ID or URL.
+        job: String,
+    },
+    /// Add a note to a job.
+    Note {
+        /// The job's ID or URL.
+        job: String,
+        note: String,
+    },
+    /// Set or clear the date to follow up on a job.
+    FollowUp {
+        /// The job's ID or URL.
+        job: String,
+        date: Option<NaiveDate>,
+    },
+    /// Show the tracked state of a job.
+    Show {
+        /// The job's ID or URL.
+        job: String,
+    },
+    /// Override the score of a job, or clear the override.
+    Score {
+        /// The job's ID or URL.
+        job: String,
+        #[arg(allow_negative_numbers = true)]
+        score: Option<i32>,
+    },
+    /// Hide a job from the job list.
+    Hide {
+        /// The job's ID or URL.
+        job: String,
+    },
+    /// Stop hiding or snoozing a job.
+    Unhide {
+        /// The job's ID or URL.
+        job: String,
+    },
+    /// Hide a job from the job list until a date.
+    Snooze {
+        /// The job's ID or URL.
+        job: String,
+        until: NaiveDate,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum MuteKind {
+    Company,
+    Source,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    init_logger(cli.log_level.unwrap_or(match cli.command {
+        Command::Scrape { .. } => log::LevelFilter::Debug,
+        _ => log::LevelFilter::Info,
+    }));
+    let mut bot = Bot::with_data_dir(cli.data_dir);
+    bot.browser = BrowserConfig {
+        browser: cli.browser,
+        driver_path: cli.driver_path,
+        port: cli.port,
+        remote_url: cli.remote_url,
+    };
+
+    match cli.command {
+        Command::Scrape {
+            sources,
+            skip,
+            max_drop,
+            resume,
+            max_failures,
+        } => {
+            bot.health_config = HealthConfig {
+                max_drop_percent: max_drop,
+                max_failures,
+            };
+            bot.load();
+            bot.load_profile(&cli.profile)?;
+            if cli.headful {
+                bot.init_no_headless().await?;
+            } else {
+                bot.init().await?;
+            }
+            let selection = SourceSelection {
+                only: sources,
+                skip,
+            };
+            // Progress is saved as it goes, so just close the browser if interrupted.
+            match interruptible(bot.update_jobs(&selection, resume)).await {
+                Some(summary) => {
+                    print!("{}", summary);
+                    bot.notify_new_jobs(&summary.new_ids).await;
+                }
+                None => log::warn!("Interrupted, resume with --resume"),
+            }
+            bot.quit().await?;
+        }
+        Command::List { filter } => {
+            bot.load_jobs();
+            bot.load_profile(&cli.profile)?;
+            bot.list_jobs(&filter.into());
+        }
+        Command::Fix => {
+            bot.load_jobs();
+            bot.fix_jobs();
+            bot.save_jobs();
+        }
+        Command::Explain { job } => {
+            bot.load_jobs();
+            bot.load_profile(&cli.profile)?;
+            explain(&bot, &job);
+        }
+        Command::Export {
+            filter,
+            format,
+            output,
+        } => {
+            bot.load_jobs();
+            bot.load_profile(&cli.profile)?;
+            let jobs = bot.filtered_jobs(&filter.into());
+            let jobs_str = format.export(&jobs, &bot.profile);
+            match output {
+                Some(path) => std::fs::write(path, jobs_str)?,
+                None => print!("{}", jobs_str),
+            }
+        }
+        Command::Feed {
+            filter,
+            feed_title,
+            output,
+        } => {
+            bot.load_jobs();
+            bot.load_profile(&cli.profile)?;
+            let filter = JobFilter {
+                sort: JobSort::Age,
+                reverse: false,
+                ..filter.into()
+            };
+            let jobs = bot
+                .filtered_jobs(&filter)
+                .into_iter()
+                .map(|(id, job)| (id.as_str(), bot.profile.score(id, job), job));
+            let feed = Feed::new(feed_title, jobs).to_atom();
+            match output {
+                Some(path) => std::fs::write(path, feed)?,
+                None => print!("{}", feed),
+            }
+        }
+        Command::Sources => {
+            bot.load();
+            for source in &bot.job_sources {
+                let num_jobs = bot
+                    .jobs
+                    .values()
+                    .filter(|job| job.source == source.name && job.missing_since.is_none())
+                    .count();
+                let health = bot.health.get(&source.name);
+                let last_run = match health.and_then(|x| x.runs.last()) {
+                    Some(run) if run.error.is_some() => {
+                        format!("failed {} times", health.unwrap().failures())
+                    }
+                    Some(run) => format!(
+                        "found {} on {}",
+                        run.jobs,
+                        run.time.with_timezone(&Local).format("%Y-%m-%d"),
+                    ),
+                    None => "never scraped".to_string(),
+                };
+                println!(
+                    "{:24} {:>4} jobs, {:24} ({})",
+                    source.name, num_jobs, last_run, source.url,
+                );
+            }
+        }
+        Command::Validate => {
+            let problems = bot.validate_job_sources();
+            for problem in &problems {
+                println!("{}", problem);
+            }
+            if !problems.is_empty() {
+                log::error!("Found {} problems", problems.len());
+                std::process::exit(1);
+            }
+            log::info!("No problems found");
+        }
+        Command::Author { target } => {
+            if !std::path::Path::new(&target).is_file() {
+                bot.init_no_headless().await?;
+            }
+            let driver = bot.driver.as_ref();
+            // Close the browser even if authoring fails or is interrupted.
+            let result = interruptible(async {
+                let mut author = SourceAuthor::open(&target, driver).await?;
+                author.run(driver).await
+            })
+            .await;
+            bot.quit().await?;
+            match result {
+                Some(result) => result?,
+                None => log::warn!("Interrupted"),
+            }
+        }
+        Command::Infer { target, edit } => {
+            if !std::path::Path::new(&target).is_file() {
+                if cli.headful || edit {
+                    bot.init_no_headless().await?;
+                } else {
+                    bot.init().await?;
+                }
+            }
+            let driver = bot.driver.as_ref();
+            // Close the browser even if inference fails or is interrupted.
+            let result = interruptible(async {
+                let author = SourceAuthor::open(&target, driver).await?;
+                match author.infer() {
+                    Some(mut author) if edit => author.run(driver).await?,
+                    Some(author) => {
+                        author.preview(10);
+                        println!("{}", author.to_ron());
+                    }
+                    None => log::error!("Couldn't find a list of jobs in {}", target),
+                }
+                WebDriverResult::Ok(())
+            })
+            .await;
+            bot.quit().await?;
+            match result {
+                Some(result) => result?,
+                None => log::warn!("Interrupted"),
+            }
+        }
+        Command::Notify { days } => {
+            bot.load_jobs();
+            bot.load_notify();
+            bot.load_profile(&cli.profile)?;
+            let now = Utc::now();
+            let ids = bot
+                .jobs
+                .iter()
+                .filter(|(_, job)| job.missing_since.is_none())
+                .filter(|(_, job)| (now - job.first_seen).num_days() < days)
+                .map(|(id, _)| id.clone())
+                .collect::<Vec<_>>();
+            bot.notify_new_jobs(&ids).await;
+            bot.post_new_jobs(&ids).await;
+        }
+        #[cfg(feature = "server")]
+        Command::Serve { port } => {
+            bot.load_jobs();
+            bot.load_profile(&cli.profile)?;
+            find_a_job::serve(bot, port).await?;
+        }
+        #[cfg(feature = "tui")]
+        Command::Tui => {
+            bot.load_jobs();
+            bot.load_profile(&cli.profile)?;
+            find_a_job::run_tui(&mut bot)?;
+        }
+        Command::Track { command } => {
+            bot.load_jobs();
+            bot.load_profile(&cli.profile)?;
+            track(&mut bot, command);
+        }
+        Command::Mute { kind, name } => {
+            bot.load_profile(&cli.profile)?;
+            mute(&mut bot, kind, &name, true);
+        }
+        Command::Unmute { kind, name } => {
+            bot.load_profile(&cli.profile)?;
+            mute(&mut bot, kind, &name, false);
+        }
+    }
+
+    Ok(())
+}
+
+/// Waits for a future, or returns `None` if interrupted with Ctrl-C or SIGTERM first.
+async fn interruptible<T>(future: impl Future<Output = T>) -> Option<T> {
+    tokio::select! {
+        x = future => Some(x),
+        _ = interrupted() => None,
+    }
+}
+
+/// Waits for Ctrl-C, or for SIGTERM on Unix, which is how cron and systemd stop a command.
+async fn interrupted() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
+            }
+            return;
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+fn explain(bot: &Bot, id_or_url: &str) {
+    let Some(id) = bot.find_job_id(id_or_url) else {
+        log::error!("No job found with ID or URL: {}", id_or_url);
+        return;
+    };
+    let Some(job) = bot.get_job(&id) else {
+        log::error!("No job found with ID or URL: {}", id_or_url);
+        return;
+    };
+
+    let config = &bot.profile.score;
+    println!("[{}] {} ({})", job.company, job, job.url);
+    println!("ID: {}", id);
+    println!("Level: {:?}", job.level);
+    println!("Discipline: {:?}", job.discipline);
+    if let Some(specialty) = job.specialty {
+        println!("Specialty: {:?}", specialty);
+    }
+    if job.is_general_application {
+        println!("General application");
+    }
+    println!();
+    for (field, score) in job.score_breakdown(config) {
+        println!("{:>24}: {:+}", field, score);
+    }
+    println!("{:>24}: {:+}", "total", job.score(config));
+    if let Some(score) = bot.profile.jobs.get(&id).and_then(|x| x.score_override) {
+        println!("{:>24}: {:+}", "override", score);
+    }
+}
+
+fn track(bot: &mut Bot, command: TrackCommand) {
+    let id = match &command {
+        TrackCommand::Status { job, .. }
+        | TrackCommand::Untrack { job }
+        | TrackCommand::Note { job, .. }
+        | TrackCommand::FollowUp { job, .. }
+        | TrackCommand::Score { job, .. }
+        | TrackCommand::Show { job }
+        | TrackCommand::Hide { job }
+        | TrackCommand::Unhide { job }
+        | TrackCommand::Snooze { job, .. } => job,
+    };
+    let Some(state) = bot.track_job(id) else {
+        log::error!("No job found with ID or URL: {}", id);
+        return;
+    };
+    match command {
+        TrackCommand::Status { status, date, .. } => state.set_status(Some(status), date),
+        TrackCommand::Untrack { .. } => state.set_status(None, None),
+        TrackCommand::Note { note, .. } => state.add_note(note),
+        TrackCommand::FollowUp { date, .. } => state.follow_up = date,
+        TrackCommand::Score { score, .. } => state.score_override = score,
+        TrackCommand::Hide { .. } => state.hidden = true,
+        TrackCommand::Unhide { .. } => {
+            state.hidden = false;
+            state.snoozed_until = None;
+        }
+        TrackCommand::Snooze { until, .. } => state.snoozed_until = Some(until),
+        TrackCommand::Show { .. } => {
+            if let Some(job) = &state.job {
+                println!("[{}] {} ({})", job.company, job, job.url);
+            }
+            if state.hidden {
+                println!("Hidden");
+            }
+            if let Some(date) = state.snoozed_until {
+                println!("Snoozed until: {}", date);
+            }
+            if let Some(status) = state.status {
+                println!("Status: {}", status);
+            }
+            if let Some(date) = state.applied_on {
+                println!("Applied on: {}", date);
+            }
+            if let Some(date) = state.follow_up {
+                println!("Follow up on: {}", date);
+            }
+            if let Some(score) = state.score_override {
+                println!("Score override: {}", score);
+            }
+            for (time, status) in &state.history {
+                let status = status.map(|x| x.to_string());
+                println!(
+                    "{}: {}",
+                    time.date_naive(),
+                    status.as_deref().unwrap_or("untracked")
+                );
+            }
+            for (time, note) in &state.notes {
+                println!("{}: {}", time.date_naive(), note);
+            }
+            return;
+        }
+    }
+    bot.save_profile();
+}
+
+fn mute(bot: &mut Bot, kind: MuteKind, name: &str, mute: bool) {
+    let muted = match kind {
+        MuteKind::Company => &mut bot.profile.muted_companies,
+        MuteKind::Source => &mut bot.profile.muted_sources,
+    };
+    // Names are matched case-insensitively, like when hiding jobs.
+    let is_muted = muted.iter().any(|x| x.eq_ignore_ascii_case(name));
+    if mute {
+        if is_muted {
+            log::info!("Already muted: {}", name);
+            return;
+        }
+        muted.insert(name.to_string());
+    } else {
+        if !is_muted {
+            log::error!("Not muted: {}", name);
+            return;
+        }
+        muted.retain(|x| !x.eq_ignore_ascii_case(name));
+    }
+    bot.save_profile();
+}
+
+/// A helper function to transition a job source off of using URLs as IDs.
+#[allow(unused)]
+fn url_to_id<'a>(jobs: impl IntoIterator<Item = &'a mut Job>, source: impl AsRef<str>) {
+    let jobs_by_url_str = std::fs::read_to_string("data/jobs.backup.ron").unwrap();
+    let jobs_by_url: HashMap<String, Job> = ron::from_str(&jobs_by_url_str).unwrap();
+    for job in jobs.into_iter() {
+        if !job.source.starts_with(source.as_ref()) {
+            continue;
+        }
+        job.first_seen = jobs_by_url[job.url.as_str()].first_seen;
+        job.missing_since = jobs_by_url[job.url.as_str()].missing_since;
+    }
+}
@@ -0,0 +1,176 @@
+use chrono::Utc;
+use html_escape::{encode_double_quoted_attribute, encode_text};
+use lettre::{
+    message::{Mailbox, MultiPart},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport as _, Message, Tokio1Executor,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{job::Job, webhook::WebhookConfig};
+
+/// Where to send notifications about new jobs.
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotifyConfig {
+    pub email: Option<EmailConfig>,
+    pub webhooks: Vec<WebhookConfig>,
+}
+
+/// An SMTP server to send a digest email of new jobs through.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct EmailConfig {
+    pub host: String,
+    /// The port of the SMTP server [default: depends on `tls`].
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub tls: SmtpTls,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+    /// Only include jobs with at least this score.
+    #[serde(default = "default_min_score")]
+    pub min_score: i32,
+}
+
+fn default_min_score() -> i32 {
+    1
+}
+
+/// How to secure the connection to an SMTP server.
+#[derive(Serialize, Deserialize, Default, Clone, Copy, Debug)]
+pub enum SmtpTls {
+    /// Upgrade the connection with STARTTLS (port 587).
+    #[default]
+    StartTls,
+    /// Connect with TLS (port 465).
+    Tls,
+    /// Don't encrypt the connection, e.g. for a local SMTP sink (port 25).
+    None,
+}
+
+/// A digest of new jobs, best first.
+pub struct Digest<'a> {
+    pub jobs: Vec<(i32, &'a Job)>,
+}
+
+impl<'a> Digest<'a> {
+    /// Builds a digest of the jobs with at least a minimum score.
+    pub fn new(jobs: impl IntoIterator<Item = (i32, &'a Job)>, min_score: i32) -> Self {
+        let mut jobs = jobs
+            .into_iter()
+            .filter(|&(score, _)| score >= min_score)
+            .collect::<Vec<_>>();
+        jobs.sort_by_key(|&(score, job)| (-score, &job.company, &job.title));
+        Self { jobs }
+    }
+
+    pub fn subject(&self) -> String {
+        match self.jobs.len() {
+            1 => "1 new job".to_string(),
+            n => format!("{} new jobs", n),
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let now = Utc::now();
+        let mut text = String::new();
+        for (score, job) in &self.jobs {
+            text += &format!(
+                "[{}] {} (score {}, {} days old)\n{}\n\n",
+                job.company,
+                job,
+                score,
+                (now - job.first_seen).num_days(),
+                job.url,
+            );
+        }
+        text
+    }
+
+    pub fn to_html(&self) -> String {
+        let now = Utc::now();
+        let mut html =
+            "<table>\n<tr><th>Score</th><th>Age</th><th>Company</th><th>Title</th></tr>\n"
+                .to_string();
+        for (score, job) in &self.jobs {
+            html += &format!(
+                "<tr><td>{}</td><td>{} days</td><td>{}</td><td><a href=\"{}\">{}</a></td></tr>\n",
+                score,
+                (now - job.first_seen).num_days(),
+                encode_text(&job.company),
+                encode_double_quoted_attribute(job.url.as_str()),
+                encode_text(&job.to_string()),
+            );
+        }
+        html += "</table>\n";
+        html
+    }
+}
+
+impl EmailConfig {
+    /// Sends a digest email, unless it's empty.
+    pub async fn send(&self, digest: &Digest<'_>) -> Result<(), Box<dyn std::error::Error>> {
+        if digest.jobs.is_empty() {
+            log::info!("No new jobs to email");
+            return Ok(());
+        }
+
+        let mut message = Message::builder()
+            .from(self.from.parse::<Mailbox>()?)
+            .subject(digest.subject());
+        for to in &self.to {
+            message = message.to(to.parse::<Mailbox>()?);
+        }
+        let message = message.multipart(MultiPart::alternative_plain_html(
+            digest.to_text(),
+            digest.to_html(),
+        ))?;
+
+        let mut transport = match self.tls {
+            SmtpTls::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.host)?,
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&self.host)?,
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&self.host),
+        };
+        if let Some(port) = self.port {
+            transport = transport.port(port);
+        }
+        if let (Some(username), Some(password)) = (&self.username, &self.password) {
+            transport = transport.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        transport.build().send(message).await?;
+
+        log::info!(
+            "Emailed {} new jobs to {}",
+            digest.jobs.len(),
+            self.to.join(", "),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use super::*;
+
+    #[test]
+    fn digest() {
+        let url = Url::parse("https://example.com/jobs/1?a=1&b=2").unwrap();
+        let good = Job::new("Source", "Tom & Co", url.clone(), "Gameplay Programmer");
+        let bad = Job::new("Source", "Company", url, "Accountant");
+        let digest = Digest::new([(-2, &bad), (6, &good)], 1);
+        assert_eq!(digest.jobs.len(), 1);
+        assert_eq!(digest.subject(), "1 new job");
+        assert!(digest.to_text().contains("[Tom & Co] "));
+        let html = digest.to_html();
+        assert!(html.contains("Tom &amp; Co"));
+        assert!(html.contains("?a=1&amp;b=2"));
+    }
+}
@@ -0,0 +1,176 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::job::{Job, ScoreConfig};
+
+/// A user's preferences and per-job state on top of the shared job database.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// The name of the profile (also its file name).
+    #[serde(skip)]
+    pub name: String,
+    /// The preferences for scoring jobs.
+    #[serde(default)]
+    pub score: ScoreConfig,
+    /// The user's state for each job by job ID.
+    #[serde(default)]
+    pub jobs: HashMap<String, JobState>,
+    /// Companies whose jobs are hidden.
+    #[serde(default)]
+    pub muted_companies: BTreeSet<String>,
+    /// Job sources whose jobs are hidden.
+    #[serde(default)]
+    pub muted_sources: BTreeSet<String>,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_NAME)
+    }
+}
+
+impl Profile {
+    pub const DEFAULT_NAME: &str = "default";
+
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            score: ScoreConfig::default(),
+            jobs: HashMap::new(),
+            muted_companies: BTreeSet::new(),
+            muted_sources: BTreeSet::new(),
+        }
+    }
+
+    /// Checks that a profile name is safe to use as a file name in the profiles directory.
+    pub fn parse_name(name: &str) -> Result<String, String> {
+        if name.is_empty() || name == "." || name == ".." {
+            return Err(format!("invalid profile name: {:?}", name));
+        }
+        if name.contains(['/', '\\']) {
+            return Err("profile names can't contain path separators".to_string());
+        }
+        Ok(name.to_string())
+    }
+
+    /// True if the job is hidden, snoozed, or from a muted company or source.
+    pub fn is_hidden(&self, id: &str, job: &Job) -> bool {
+        self.jobs.get(id).is_some_and(JobState::is_hidden)
+            || self
+                .muted_companies
+                .iter()
+                .any(|x| x.eq_ignore_ascii_case(&job.company))
+            || self
+                .muted_sources
+                .iter()
+                .any(|x| x.eq_ignore_ascii_case(&job.source))
+    }
+
+    pub fn status(&self, id: &str) -> Option<JobStatus> {
+        self.jobs.get(id).and_then(|state| state.status)
+    }
+
+    /// The job's score override, or its score from the scoring preferences.
+    pub fn score(&self, id: &str, job: &Job) -> i32 {
+        self.jobs
+            .get(id)
+            .and_then(|state| state.score_override)
+            .unwrap_or_else(|| job.score(&self.score))
+    }
+}
+
+/// A user's state for a single job.
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct JobState {
+    /// True if the job should be left out of the job list.
+    pub hidden: bool,
+    /// The date until which the job should be left out of the job list.
+    pub snoozed_until: Option<NaiveDate>,
+    /// The job's place in the application pipeline, if it's being tracked.
+    pub status: Option<JobStatus>,
+    /// The date the application was sent.
+    pub applied_on: Option<NaiveDate>,
+    /// The date to follow up on the application.
+    pub follow_up: Option<NaiveDate>,
+    /// A score to use instead of the one from the scoring preferences.
+    pub score_override: Option<i32>,
+    /// Free-form notes with the time they were added.
+    pub notes: Vec<(DateTime<Utc>, String)>,
+    /// Every status change with the time it happened.
+    pub history: Vec<(DateTime<Utc>, Option<JobStatus>)>,
+    /// The last known copy of the job, kept after it's removed from the job database.
+    pub job: Option<Job>,
+}
+
+impl JobState {
+    /// Moves the job to a new status, recording the change in its history.
+    pub fn set_status(&mut self, status: Option<JobStatus>, date: Option<NaiveDate>) {
+        let now = Utc::now();
+        if status == Some(JobStatus::Applied) {
+            self.applied_on = Some(date.unwrap_or(now.date_naive()));
+        }
+        self.status = status;
+        self.history.push((now, status));
+    }
+
+    /// True if the job is hidden or still snoozed.
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+            || self
+                .snoozed_until
+                .is_some_and(|date| date > Utc::now().date_naive())
+    }
+
+    pub fn add_note(&mut self, note: impl Into<String>) {
+        self.notes.push((Utc::now(), note.into()));
+    }
+
+    /// True if the follow-up date has arrived.
+    pub fn is_follow_up_due(&self) -> bool {
+        self.follow_up
+            .is_some_and(|date| date <= Utc::now().date_naive())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug, clap::ValueEnum)]
+pub enum JobStatus {
+    Interested,
+    Applied,
+    Interviewing,
+    Offer,
+    Rejected,
+    Withdrawn,
+    Ignored,
+}
+
+impl Display for JobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            JobStatus::Interested => "interested",
+            JobStatus::Applied => "applied",
+            JobStatus::Interviewing => "interviewing",
+            JobStatus::Offer => "offer",
+            JobStatus::Rejected => "rejected",
+            JobStatus::Withdrawn => "withdrawn",
+            JobStatus::Ignored => "ignored",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl JobStatus {
+    /// True if the job no longer needs attention in the default job list.
+    pub fn is_closed(self) -> bool {
+        matches!(
+            self,
+            JobStatus::Applied | JobStatus::Rejected | JobStatus::Withdrawn | JobStatus::Ignored,
+        )
+    }
+}
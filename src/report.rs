@@ -0,0 +1,202 @@
+use std::collections::BTreeSet;
+
+use chrono::{Local, Utc};
+use html_escape::{encode_double_quoted_attribute, encode_text};
+
+use crate::{job::Job, profile::Profile};
+
+const STYLE: &str = r#"
+body { background: #1e1e1e; color: #d4d4d4; font-family: monospace; margin: 1em; }
+table { border-collapse: collapse; }
+th { cursor: pointer; text-align: left; user-select: none; }
+th, td { padding: 0.1em 0.6em; white-space: nowrap; }
+tr:hover { background: #2a2a2a; }
+a { color: inherit; text-decoration: none; }
+a:hover { text-decoration: underline; }
+.today { color: #11a8cd; font-weight: bold; }
+.week { color: rgb(200, 150, 60); }
+.old { color: #cd3131; }
+.status { color: #2472c8; }
+.due { color: #e5e510; }
+.good { color: #0dbc79; }
+.bad { color: #cd3131; }
+.url { color: #808080; font-style: italic; }
+#filters { margin-bottom: 1em; }
+#filters * { margin-right: 0.6em; }
+"#;
+
+const SCRIPT: &str = r##"
+const rows = [...document.querySelectorAll("tbody tr")];
+const filters = [...document.querySelectorAll("#filters [data-key]")];
+function filter() {
+    let shown = 0;
+    for (const row of rows) {
+        const show = filters.every((x) => {
+            const value = x.value.toLowerCase();
+            const field = row.dataset[x.dataset.key].toLowerCase();
+            const matches = x.tagName === "INPUT" ? field.includes(value) : field === value;
+            return value === "" || matches;
+        });
+        row.hidden = !show;
+        shown += show;
+    }
+    document.getElementById("count").textContent = shown;
+}
+for (const x of filters) {
+    x.addEventListener("input", filter);
+}
+
+let sorted = { column: -1, descending: false };
+function sort(column) {
+    sorted.descending = sorted.column === column && !sorted.descending;
+    sorted.column = column;
+    const key = (row) => row.cells[column].dataset.sort ?? row.cells[column].textContent;
+    rows.sort((a, b) => {
+        const [x, y] = [key(a), key(b)];
+        const order = isNaN(x) || isNaN(y) ? x.localeCompare(y) : x - y;
+        return sorted.descending ? -order : order;
+    });
+    document.querySelector("tbody").append(...rows);
+}
+document.querySelectorAll("th").forEach((x, i) => x.addEventListener("click", () => sort(i)));
+"##;
+
+/// Renders the jobs as a self-contained HTML page that looks like `list`, in the given order.
+///
+/// Each job title links to `job_link(id, job)`.
+pub(crate) fn html_report(
+    jobs: &[(&String, &Job)],
+    profile: &Profile,
+    job_link: impl Fn(&str, &Job) -> String,
+) -> String {
+    let now = Utc::now();
+    let mut levels = BTreeSet::new();
+    let mut specialties = BTreeSet::new();
+    let mut disciplines = BTreeSet::new();
+    let mut rows = String::new();
+    for &(id, job) in jobs {
+        let age = (now - job.first_seen).num_days();
+        let score = profile.score(id, job);
+        let state = profile.jobs.get(id);
+        let status = state
+            .and_then(|x| x.status)
+            .map(|x| x.to_string())
+            .unwrap_or_default();
+        let level = format!("{:?}", job.level);
+        let specialty = job
+            .specialty
+            .map(|x| format!("{:?}", x))
+            .unwrap_or_default();
+        let discipline = format!("{:?}", job.discipline);
+
+        rows += &format!(
+            "<tr data-level=\"{}\" data-specialty=\"{}\" data-discipline=\"{}\" \
+             data-company=\"{}\">\
+             <td class=\"{}\" data-sort=\"{}\">{} days ago</td>\
+             <td class=\"{}\">{}</td>\
+             <td>{}</td>\
+             <td class=\"{}\"><a href=\"{}\">{}</a></td>\
+             <td class=\"{}\">{}</td>\
+             <td>{}</td><td>{}</td><td>{}</td><td>{}</td>\
+             <td class=\"url\">{}</td></tr>\n",
+            level,
+            specialty,
+            discipline,
+            encode_double_quoted_attribute(&job.company),
+            if age == 0 {
+                "today"
+            } else if age < 7 {
+                "week"
+            } else {
+                "old"
+            },
+            age,
+            age,
+            if state.is_some_and(|x| x.is_follow_up_due()) {
+                "due"
+            } else {
+                "status"
+            },
+            status,
+            encode_text(&job.company),
+            if score > 0 { "good" } else { "bad" },
+            encode_double_quoted_attribute(&job_link(id, job)),
+            encode_text(&job.title),
+            if score > 0 { "good" } else { "bad" },
+            score,
+            level,
+            specialty,
+            discipline,
+            encode_text(&job.source),
+            encode_text(job.url.as_str()),
+        );
+
+        levels.insert(level);
+        specialties.insert(specialty);
+        disciplines.insert(discipline);
+    }
+    specialties.remove("");
+
+    let select = |key: &str, label: &str, options: &BTreeSet<String>| {
+        let mut html = format!(
+            "<select data-key=\"{}\"><option value=\"\">Any {}</option>",
+            key, label,
+        );
+        for option in options {
+            html += &format!("<option>{}</option>", option);
+        }
+        html + "</select>"
+    };
+
+    format!(
+        "<!DOCTYPE html>\n\
+         <html>\n\
+         <head>\n\
+         <meta charset=\"utf-8\">\n\
+         <title>Jobs</title>\n\
+         <style>{}</style>\n\
+         </head>\n\
+         <body>\n\
+         <p><span id=\"count\">{}</span> jobs, generated {}</p>\n\
+         <div id=\"filters\">{}{}{}<input data-key=\"company\" placeholder=\"Company\"></div>\n\
+         <table>\n\
+         <thead><tr><th>Age</th><th>Status</th><th>Company</th><th>Title</th><th>Score</th>\
+         <th>Level</th><th>Specialty</th><th>Discipline</th><th>Source</th><th>URL</th>\
+         </tr></thead>\n\
+         <tbody>\n{}</tbody>\n\
+         </table>\n\
+         <script>{}</script>\n\
+         </body>\n\
+         </html>\n",
+        STYLE,
+        jobs.len(),
+        Local::now().format("%Y-%m-%d %H:%M"),
+        select("level", "level", &levels),
+        select("specialty", "specialty", &specialties),
+        select("discipline", "discipline", &disciplines),
+        rows,
+        SCRIPT,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use super::*;
+
+    #[test]
+    fn report() {
+        let url = Url::parse("https://example.com/jobs/1?a=1&b=2").unwrap();
+        let job = Job::new("Source", "Tom & Co", url, "Senior Graphics Programmer");
+        let id = "id".to_string();
+        let html = html_report(&[(&id, &job)], &Profile::default(), |_, job| {
+            job.url.to_string()
+        });
+        assert!(html.contains("data-company=\"Tom &amp; Co\""));
+        assert!(html.contains("<td class=\"today\" data-sort=\"0\">0 days ago</td>"));
+        assert!(html.contains("<a href=\"https://example.com/jobs/1?a=1&amp;b=2\">"));
+        assert!(html.contains("<option>Graphics</option>"));
+        assert!(!html.contains("<option></option>"));
+    }
+}
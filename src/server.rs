@@ -0,0 +1,346 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::{Form, Path, Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::{self, Next},
+    response::{Html, IntoResponse as _, Redirect, Response},
+    routing::{get, post},
+    Router,
+};
+use chrono::{Local, Utc};
+use clap::ValueEnum as _;
+use html_escape::{encode_double_quoted_attribute, encode_text};
+use serde::Deserialize;
+use url::{form_urlencoded::byte_serialize, Url};
+
+use crate::{bot::Bot, filter::JobFilter, profile::JobStatus, report::html_report};
+
+type SharedBot = Arc<Mutex<Bot>>;
+
+/// Serves a web UI for browsing and triaging jobs on localhost until interrupted.
+///
+/// The bot should have its jobs and profile loaded. Changes are saved to the profile.
+pub async fn serve(bot: Bot, port: u16) -> std::io::Result<()> {
+    let app = Router::new()
+        .route("/", get(index))
+        .route("/job", get(job_page))
+        .route("/job/{action}", post(job_action))
+        .layer(middleware::from_fn_with_state(port, same_origin))
+        .with_state(Arc::new(Mutex::new(bot)));
+
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port)).await?;
+    log::info!("Serving the job list at http://localhost:{}", port);
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+}
+
+/// Rejects requests to other hosts or from other sites, so that other pages open in the browser
+/// can't read or change the jobs.
+async fn same_origin(State(port): State<u16>, request: Request, next: Next) -> Response {
+    if let Err(e) = check_origin(request.headers(), port) {
+        return (StatusCode::FORBIDDEN, e).into_response();
+    }
+    next.run(request).await
+}
+
+fn check_origin(headers: &HeaderMap, port: u16) -> Result<(), &'static str> {
+    let is_local = |host: Option<&str>, request_port: Option<u16>| {
+        matches!(host, Some("localhost" | "127.0.0.1")) && request_port == Some(port)
+    };
+    let header = |name| headers.get(name).and_then(|x| x.to_str().ok());
+
+    let host = header(header::HOST).and_then(|x| x.rsplit_once(':'));
+    if !is_local(host.map(|x| x.0), host.and_then(|x| x.1.parse().ok())) {
+        return Err("Unexpected host");
+    }
+    // Browsers send the origin with every POST, so only forms from this UI get through.
+    if let Some(origin) = header(header::ORIGIN).or(header(header::REFERER)) {
+        let origin = Url::parse(origin).ok();
+        let origin = origin.as_ref().filter(|x| x.scheme() == "http");
+        if !is_local(origin.and_then(Url::host_str), origin.and_then(Url::port)) {
+            return Err("Unexpected origin");
+        }
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct IndexQuery {
+    /// Include jobs that are hidden, snoozed, or from a muted company or source.
+    #[serde(default)]
+    show_hidden: bool,
+    /// Include jobs that are missing from their job source.
+    #[serde(default)]
+    include_missing: bool,
+}
+
+/// The ranked job list, most relevant first.
+async fn index(State(bot): State<SharedBot>, Query(query): Query<IndexQuery>) -> Html<String> {
+    let bot = bot.lock().unwrap();
+    let filter = JobFilter {
+        show_hidden: query.show_hidden,
+        include_missing: query.include_missing,
+        reverse: true,
+        ..Default::default()
+    };
+    let jobs = bot.filtered_jobs(&filter);
+    Html(html_report(&jobs, &bot.profile, |id, _| job_path(id)))
+}
+
+#[derive(Deserialize)]
+struct JobQuery {
+    id: String,
+}
+
+/// A job's details, scoring, and tracked state, with forms for triage actions.
+async fn job_page(State(bot): State<SharedBot>, Query(query): Query<JobQuery>) -> Response {
+    let bot = bot.lock().unwrap();
+    let id = &query.id;
+    let state = bot.profile.jobs.get(id);
+    let Some(job) = bot.get_job(id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            format!("No job found with ID: {}", id),
+        )
+            .into_response();
+    };
+
+    let now = Utc::now();
+    let mut html = format!(
+        "<h2>[{}] <a href=\"{}\">{}</a></h2>\n<p><a href=\"/\">Back to jobs</a></p>\n<table>\n",
+        encode_text(&job.company),
+        encode_double_quoted_attribute(job.url.as_str()),
+        encode_text(&job.title),
+    );
+    let mut row = |key: &str, value: &str| {
+        html += &format!("<tr><th>{}</th><td>{}</td></tr>\n", key, encode_text(value));
+    };
+    row("ID", id);
+    row("Source", &job.source);
+    row(
+        "First seen",
+        &format!(
+            "{} ({} days ago)",
+            job.first_seen.with_timezone(&Local).format("%Y-%m-%d"),
+            (now - job.first_seen).num_days(),
+        ),
+    );
+    if let Some(missing_since) = job.missing_since {
+        row(
+            "Missing since",
+            &missing_since
+                .with_timezone(&Local)
+                .format("%Y-%m-%d")
+                .to_string(),
+        );
+    }
+    if !bot.jobs.contains_key(id) {
+        row("Removed", "yes");
+    }
+    row("Level", &format!("{:?}", job.level));
+    row("Discipline", &format!("{:?}", job.discipline));
+    if let Some(specialty) = job.specialty {
+        row("Specialty", &format!("{:?}", specialty));
+    }
+    if job.is_general_application {
+        row("General application", "yes");
+    }
+    for (field, score) in job.score_breakdown(&bot.profile.score) {
+        row(&format!("Score: {}", field), &format!("{:+}", score));
+    }
+    row("Score: total", &format!("{:+}", bot.profile.score(id, job)));
+    if let Some(state) = state {
+        if let Some(score) = state.score_override {
+            row("Score override", &format!("{:+}", score));
+        }
+        if state.is_hidden() {
+            row("Hidden", "yes");
+        }
+        if let Some(date) = state.snoozed_until {
+            row("Snoozed until", &date.to_string());
+        }
+        if let Some(status) = state.status {
+            row("Status", &status.to_string());
+        }
+        if let Some(date) = state.applied_on {
+            row("Applied on", &date.to_string());
+        }
+        if let Some(date) = state.follow_up {
+            row("Follow up on", &date.to_string());
+        }
+    }
+    html += "</table>\n";
+
+    let id_input = format!(
+        "<input type=\"hidden\" name=\"id\" value=\"{}\">",
+        encode_double_quoted_attribute(id),
+    );
+    let hidden = state.is_some_and(|x| x.is_hidden());
+    html += &format!(
+        "<form method=\"post\" action=\"/job/{}\">{}<button>{}</button></form>\n",
+        if hidden { "unhide" } else { "hide" },
+        id_input,
+        if hidden { "Unhide" } else { "Hide" },
+    );
+    let status = state.and_then(|x| x.status);
+    html += &format!(
+        "<form method=\"post\" action=\"/job/status\">{}<select name=\"value\">\
+         <option value=\"\">untracked</option>",
+        id_input,
+    );
+    for &option in JobStatus::value_variants() {
+        html += &format!(
+            "<option{}>{}</option>",
+            if status == Some(option) {
+                " selected"
+            } else {
+                ""
+            },
+            option,
+        );
+    }
+    html += "</select><button>Set status</button></form>\n";
+    html += &format!(
+        "<form method=\"post\" action=\"/job/score\">{}\
+         <input type=\"number\" name=\"value\" value=\"{}\" placeholder=\"No override\">\
+         <button>Override score</button></form>\n",
+        id_input,
+        state
+            .and_then(|x| x.score_override)
+            .map(|x| x.to_string())
+            .unwrap_or_default(),
+    );
+    html += &format!(
+        "<form method=\"post\" action=\"/job/note\">{}\
+         <input name=\"value\" size=\"60\" placeholder=\"Note\"><button>Add note</button></form>\n",
+        id_input,
+    );
+
+    if let Some(state) = state {
+        html += "<h3>History</h3>\n<ul>\n";
+        for (time, status) in &state.history {
+            html += &format!(
+                "<li>{}: {}</li>\n",
+                time.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+                status.map_or("untracked".to_string(), |x| x.to_string()),
+            );
+        }
+        html += "</ul>\n<h3>Notes</h3>\n<ul>\n";
+        for (time, note) in &state.notes {
+            html += &format!(
+                "<li>{}: {}</li>\n",
+                time.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+                encode_text(note),
+            );
+        }
+        html += "</ul>\n";
+    }
+
+    Html(format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>body {{ font-family: sans-serif; margin: 1em; }} th {{ text-align: left; }} \
+         form {{ margin: 0.4em 0; }}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        encode_text(&job.title),
+        html,
+    ))
+    .into_response()
+}
+
+#[derive(Deserialize)]
+struct ActionForm {
+    id: String,
+    #[serde(default)]
+    value: String,
+}
+
+/// Applies a triage action to a job and saves the profile.
+async fn job_action(
+    State(bot): State<SharedBot>,
+    Path(action): Path<String>,
+    Form(form): Form<ActionForm>,
+) -> Response {
+    let mut bot = bot.lock().unwrap();
+    let Some(state) = bot.track_job(&form.id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            format!("No job found with ID: {}", form.id),
+        )
+            .into_response();
+    };
+
+    let value = form.value.trim();
+    match action.as_str() {
+        "hide" => state.hidden = true,
+        "unhide" => {
+            state.hidden = false;
+            state.snoozed_until = None;
+        }
+        "status" if value.is_empty() => state.set_status(None, None),
+        "status" => match JobStatus::from_str(value, true) {
+            Ok(status) => state.set_status(Some(status), None),
+            Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+        },
+        "score" if value.is_empty() => state.score_override = None,
+        "score" => match value.parse() {
+            Ok(score) => state.score_override = Some(score),
+            Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+        },
+        "note" if !value.is_empty() => state.add_note(value),
+        "note" => {}
+        _ => return StatusCode::NOT_FOUND.into_response(),
+    }
+    bot.save_profile();
+
+    Redirect::to(&job_path(&form.id)).into_response()
+}
+
+fn job_path(id: &str) -> String {
+    format!(
+        "/job?id={}",
+        byte_serialize(id.as_bytes()).collect::<String>()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn origin() {
+        let headers = |pairs: &[(header::HeaderName, &'static str)]| {
+            let mut headers = HeaderMap::new();
+            for (name, value) in pairs {
+                headers.insert(name, HeaderValue::from_static(value));
+            }
+            headers
+        };
+        let ok = |pairs| check_origin(&headers(pairs), 8080).is_ok();
+
+        assert!(ok(&[(header::HOST, "localhost:8080")]));
+        assert!(ok(&[
+            (header::HOST, "127.0.0.1:8080"),
+            (header::ORIGIN, "http://127.0.0.1:8080"),
+        ]));
+        assert!(ok(&[
+            (header::HOST, "localhost:8080"),
+            (header::REFERER, "http://localhost:8080/job?id=1"),
+        ]));
+        assert!(!ok(&[]));
+        assert!(!ok(&[(header::HOST, "evil.example:8080")]));
+        assert!(!ok(&[(header::HOST, "localhost:8081")]));
+        assert!(!ok(&[
+            (header::HOST, "localhost:8080"),
+            (header::ORIGIN, "https://evil.example"),
+        ]));
+        assert!(!ok(&[
+            (header::HOST, "localhost:8080"),
+            (header::ORIGIN, "null"),
+        ]));
+    }
+}
@@ -0,0 +1,92 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{health::Anomaly, job::Job};
+
+/// What changed in the job database during a scrape run.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ScrapeSummary {
+    pub started: DateTime<Utc>,
+    pub seconds: f64,
+    pub sources: Vec<SourceSummary>,
+    /// The new jobs with the highest scores, best first.
+    pub top_new_jobs: Vec<(i32, Job)>,
+    /// The IDs of the new jobs, including those found before the run was resumed.
+    #[serde(skip)]
+    pub new_ids: Vec<String>,
+}
+
+/// What changed in the job database after scraping a job source.
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct SourceSummary {
+    pub name: String,
+    /// The number of jobs found.
+    pub jobs: usize,
+    pub new: usize,
+    pub missing: usize,
+    pub recovered: usize,
+    /// The number of jobs removed after being missing for too long.
+    pub removed: usize,
+    pub seconds: f64,
+    /// The error that stopped the scrape, if any.
+    pub error: Option<String>,
+    /// A sign that the job source is broken, if any.
+    pub anomaly: Option<Anomaly>,
+    /// The IDs of the new jobs.
+    #[serde(skip)]
+    pub new_ids: Vec<String>,
+}
+
+impl ScrapeSummary {
+    /// The number of top new jobs to include.
+    pub const MAX_TOP_NEW_JOBS: usize = 10;
+}
+
+impl Display for ScrapeSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Scraped {} job sources in {:.0}s, starting {}",
+            self.sources.len(),
+            self.seconds,
+            self.started.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+        )?;
+        writeln!(
+            f,
+            "{:24} {:>5} {:>5} {:>7} {:>9} {:>7} {:>6}",
+            "Source", "Jobs", "New", "Missing", "Recovered", "Removed", "Time",
+        )?;
+        for source in &self.sources {
+            write!(
+                f,
+                "{:24} {:>5} {:>5} {:>7} {:>9} {:>7} {:>5.0}s",
+                source.name,
+                source.jobs,
+                source.new,
+                source.missing,
+                source.recovered,
+                source.removed,
+                source.seconds,
+            )?;
+            if let Some(anomaly) = &source.anomaly {
+                write!(f, "  Possibly broken: {}", anomaly)?;
+                if anomaly.is_untrusted() {
+                    write!(f, " (jobs were not marked missing)")?;
+                }
+            } else if let Some(error) = &source.error {
+                write!(f, "  Failed: {}", error)?;
+            }
+            writeln!(f)?;
+        }
+
+        if !self.top_new_jobs.is_empty() {
+            writeln!(f, "Top new jobs:")?;
+        }
+        for (score, job) in &self.top_new_jobs {
+            writeln!(f, "{:>4} [{}] {} ({})", score, job.company, job, job.url)?;
+        }
+        Ok(())
+    }
+}
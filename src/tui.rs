@@ -0,0 +1,432 @@
+use chrono::{Local, Utc};
+use clap::ValueEnum as _;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize as _},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Row, Table, TableState, Wrap},
+    DefaultTerminal, Frame,
+};
+
+use crate::{
+    bot::{sorted, Bot},
+    filter::{JobFilter, JobSort},
+    profile::{JobState, JobStatus},
+};
+
+/// Runs a full-screen terminal UI for browsing and triaging jobs until the user quits.
+///
+/// The bot should have its jobs and profile loaded. Changes are saved to the profile.
+pub fn run_tui(bot: &mut Bot) -> std::io::Result<()> {
+    let mut app = App::new(bot);
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+/// A set of jobs to browse.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum View {
+    Open,
+    Missing,
+    /// The jobs removed from the job database in the last 30 days. Jobs removed before the
+    /// removed jobs were kept aren't listed.
+    Removed,
+}
+
+impl View {
+    const ALL: [Self; 3] = [Self::Open, Self::Missing, Self::Removed];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Open => "Open",
+            Self::Missing => "Missing",
+            Self::Removed => "Recently removed",
+        }
+    }
+}
+
+struct App<'a> {
+    bot: &'a mut Bot,
+    view: View,
+    sort: JobSort,
+    /// List the least relevant jobs first instead of the most relevant.
+    reverse: bool,
+    /// Also list hidden jobs in the open view, so they can be unhidden.
+    show_hidden: bool,
+    /// Only list jobs matching every word of this query.
+    query: String,
+    editing_query: bool,
+    /// The IDs of the listed jobs, in order.
+    ids: Vec<String>,
+    table: TableState,
+    /// A message about the last action, shown instead of the help line.
+    message: Option<String>,
+}
+
+impl<'a> App<'a> {
+    /// The number of rows to move on page up or down.
+    const PAGE: u16 = 10;
+
+    fn new(bot: &'a mut Bot) -> Self {
+        let mut app = Self {
+            bot,
+            view: View::Open,
+            sort: JobSort::default(),
+            reverse: false,
+            show_hidden: false,
+            query: String::new(),
+            editing_query: false,
+            ids: vec![],
+            table: TableState::default(),
+            message: None,
+        };
+        app.refresh();
+        app
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            self.message = None;
+
+            if self.editing_query {
+                match key.code {
+                    KeyCode::Enter => self.editing_query = false,
+                    KeyCode::Esc => {
+                        self.editing_query = false;
+                        self.query.clear();
+                    }
+                    KeyCode::Backspace => {
+                        self.query.pop();
+                    }
+                    KeyCode::Char(c) => self.query.push(c),
+                    _ => continue,
+                }
+                self.refresh();
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Down | KeyCode::Char('j') => self.table.select_next(),
+                KeyCode::Up | KeyCode::Char('k') => self.table.select_previous(),
+                KeyCode::PageDown => self.table.scroll_down_by(Self::PAGE),
+                KeyCode::PageUp => self.table.scroll_up_by(Self::PAGE),
+                KeyCode::Home | KeyCode::Char('g') => self.table.select_first(),
+                KeyCode::End | KeyCode::Char('G') => self.table.select_last(),
+                KeyCode::Tab | KeyCode::BackTab => {
+                    let idx = View::ALL.iter().position(|&x| x == self.view).unwrap();
+                    let step = if key.code == KeyCode::Tab { 1 } else { 2 };
+                    self.view = View::ALL[(idx + step) % View::ALL.len()];
+                    self.table.select(None);
+                    self.refresh();
+                }
+                KeyCode::Char('s') => {
+                    let sorts = JobSort::value_variants();
+                    let idx = sorts.iter().position(|&x| x == self.sort).unwrap();
+                    self.sort = sorts[(idx + 1) % sorts.len()];
+                    self.refresh();
+                }
+                KeyCode::Char('r') => {
+                    self.reverse = !self.reverse;
+                    self.refresh();
+                }
+                KeyCode::Char('H') => {
+                    self.show_hidden = !self.show_hidden;
+                    self.refresh();
+                }
+                KeyCode::Char('/') => self.editing_query = true,
+                KeyCode::Enter | KeyCode::Char('o') => self.open_selected(),
+                KeyCode::Char('a') => self.track_selected(|state| {
+                    state.set_status(Some(JobStatus::Applied), None);
+                    "Marked applied".to_string()
+                }),
+                KeyCode::Char('i') => self.track_selected(|state| {
+                    state.set_status(Some(JobStatus::Interested), None);
+                    "Marked interested".to_string()
+                }),
+                KeyCode::Char('u') => self.track_selected(|state| {
+                    state.set_status(None, None);
+                    "Untracked".to_string()
+                }),
+                KeyCode::Char('h') => self.track_selected(|state| {
+                    if state.is_hidden() {
+                        state.hidden = false;
+                        state.snoozed_until = None;
+                        "Unhidden".to_string()
+                    } else {
+                        state.hidden = true;
+                        "Hidden".to_string()
+                    }
+                }),
+                _ => {}
+            }
+        }
+    }
+
+    /// Lists the jobs in the current view that match the query, keeping the selected job.
+    fn refresh(&mut self) {
+        let selected = self.selected_id().map(|x| x.to_string());
+        let filter = JobFilter {
+            show_hidden: self.view != View::Open || self.show_hidden,
+            include_missing: self.view == View::Missing,
+            sort: self.sort,
+            reverse: !self.reverse,
+            ..Default::default()
+        };
+        let jobs = match self.view {
+            View::Open => self.bot.filtered_jobs(&filter),
+            View::Missing => self
+                .bot
+                .filtered_jobs(&filter)
+                .into_iter()
+                .filter(|(_, job)| job.missing_since.is_some())
+                .collect(),
+            View::Removed => {
+                let mut jobs = sorted(&self.bot.removed_jobs, &self.bot.profile, self.sort);
+                if !self.reverse {
+                    jobs.reverse();
+                }
+                jobs
+            }
+        };
+
+        let query = self.query.to_lowercase();
+        let words = query.split_whitespace().collect::<Vec<_>>();
+        self.ids = jobs
+            .into_iter()
+            .filter(|(_, job)| {
+                let text = format!(
+                    "{} {} {} {:?} {:?} {:?}",
+                    job.company, job.title, job.source, job.level, job.discipline, job.specialty,
+                )
+                .to_lowercase();
+                words.iter().all(|x| text.contains(x))
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        let idx = selected
+            .and_then(|id| self.ids.iter().position(|x| *x == id))
+            .or(self.table.selected())
+            .unwrap_or_default();
+        self.table
+            .select((!self.ids.is_empty()).then(|| idx.min(self.ids.len() - 1)));
+    }
+
+    fn selected_id(&self) -> Option<&str> {
+        Some(self.ids.get(self.table.selected()?)?.as_str())
+    }
+
+    fn open_selected(&mut self) {
+        let Some(job) = self.selected_id().and_then(|id| self.bot.get_job(id)) else {
+            return;
+        };
+        self.message = Some(match open::that_detached(job.url.as_str()) {
+            Ok(()) => format!("Opened {}", job.url),
+            Err(e) => format!("Couldn't open {}: {}", job.url, e),
+        });
+    }
+
+    /// Updates the selected job's state and saves the profile.
+    fn track_selected(&mut self, update: impl FnOnce(&mut JobState) -> String) {
+        let Some(id) = self.selected_id().map(|x| x.to_string()) else {
+            return;
+        };
+        let Some(state) = self.bot.track_job(&id) else {
+            return;
+        };
+        let message = update(state);
+        self.bot.save_profile();
+        self.message = Some(message);
+        self.refresh();
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [header, body, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [list, detail] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(body);
+
+        let mut spans = vec![];
+        for view in View::ALL {
+            let span = Span::raw(format!(" {} ", view.name()));
+            spans.push(if view == self.view {
+                span.reversed()
+            } else {
+                span
+            });
+        }
+        spans.push(Span::raw(format!(
+            "  Sort: {:?}{}",
+            self.sort,
+            if self.reverse { " (reversed)" } else { "" },
+        )));
+        if self.show_hidden && self.view == View::Open {
+            spans.push(Span::raw("  Showing hidden"));
+        }
+        if self.editing_query || !self.query.is_empty() {
+            spans.push(Span::raw(format!(
+                "  Filter: {}{}",
+                self.query,
+                if self.editing_query { "_" } else { "" },
+            )));
+        }
+        frame.render_widget(Line::from(spans), header);
+
+        self.draw_list(frame, list);
+        self.draw_detail(frame, detail);
+
+        let help = self.message.clone().unwrap_or_else(|| {
+            "↑↓ move  Tab view  s sort  r reverse  / filter  o open  \
+             a applied  i interested  u untrack  h hide  H show hidden  q quit"
+                .to_string()
+        });
+        frame.render_widget(Line::from(help).dim(), footer);
+    }
+
+    fn draw_list(&mut self, frame: &mut Frame, area: Rect) {
+        let now = Utc::now();
+        let profile = &self.bot.profile;
+        let rows = self
+            .ids
+            .iter()
+            .filter_map(|id| Some((id, self.bot.get_job(id)?)))
+            .map(|(id, job)| {
+                let age = (now - job.first_seen).num_days();
+                let state = profile.jobs.get(id);
+                let score = profile.score(id, job);
+                Row::new([
+                    Span::raw(format!("{:>3}d", age)).fg(if age == 0 {
+                        Color::Cyan
+                    } else if age < 7 {
+                        Color::Rgb(200, 150, 60)
+                    } else {
+                        Color::Red
+                    }),
+                    Span::raw(
+                        state
+                            .and_then(|x| x.status)
+                            .map(|x| x.to_string())
+                            .unwrap_or_default(),
+                    )
+                    .fg(if state.is_some_and(|x| x.is_follow_up_due()) {
+                        Color::Yellow
+                    } else {
+                        Color::Blue
+                    }),
+                    Span::raw(job.company.clone()),
+                    Span::raw(job.title.clone()).fg(if score > 0 {
+                        Color::Green
+                    } else {
+                        Color::Red
+                    }),
+                    Span::raw(format!("{:>5}", score)),
+                ])
+            });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(4),
+                Constraint::Length(12),
+                Constraint::Length(16),
+                Constraint::Min(16),
+                Constraint::Length(5),
+            ],
+        )
+        .header(Row::new(["Age", "Status", "Company", "Title", "Score"]).bold())
+        .block(Block::bordered().title(format!("{} ({})", self.view.name(), self.ids.len())))
+        .row_highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(table, area, &mut self.table);
+    }
+
+    fn draw_detail(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered().title("Job");
+        let Some((id, job)) = self
+            .selected_id()
+            .and_then(|id| Some((id, self.bot.get_job(id)?)))
+        else {
+            frame.render_widget(block, area);
+            return;
+        };
+
+        let now = Utc::now();
+        let date = |time: chrono::DateTime<Utc>| {
+            format!(
+                "{} ({} days ago)",
+                time.with_timezone(&Local).format("%Y-%m-%d"),
+                (now - time).num_days(),
+            )
+        };
+        let mut lines = vec![
+            Line::from(job.title.clone()).bold(),
+            Line::from(job.company.clone()),
+            Line::from(job.url.to_string()).italic().dim(),
+            Line::default(),
+            Line::from(format!("ID: {}", id)),
+            Line::from(format!("Source: {}", job.source)),
+            Line::from(format!("First seen: {}", date(job.first_seen))),
+        ];
+        if let Some(time) = job.missing_since {
+            lines.push(Line::from(format!("Missing since: {}", date(time))));
+        }
+        lines.push(Line::from(format!("Level: {:?}", job.level)));
+        lines.push(Line::from(format!("Discipline: {:?}", job.discipline)));
+        if let Some(specialty) = job.specialty {
+            lines.push(Line::from(format!("Specialty: {:?}", specialty)));
+        }
+        if job.is_general_application {
+            lines.push(Line::from("General application"));
+        }
+
+        let config = &self.bot.profile.score;
+        lines.push(Line::default());
+        for (field, score) in job.score_breakdown(config) {
+            lines.push(Line::from(format!("{:>24}: {:+}", field, score)));
+        }
+        lines.push(Line::from(format!("{:>24}: {:+}", "total", job.score(config))).bold());
+
+        if let Some(state) = self.bot.profile.jobs.get(id) {
+            if let Some(score) = state.score_override {
+                lines.push(Line::from(format!("{:>24}: {:+}", "override", score)).bold());
+            }
+            lines.push(Line::default());
+            if state.is_hidden() {
+                lines.push(Line::from("Hidden"));
+            }
+            if let Some(status) = state.status {
+                lines.push(Line::from(format!("Status: {}", status)));
+            }
+            if let Some(date) = state.applied_on {
+                lines.push(Line::from(format!("Applied on: {}", date)));
+            }
+            if let Some(date) = state.follow_up {
+                lines.push(Line::from(format!("Follow up on: {}", date)));
+            }
+            for (time, note) in &state.notes {
+                lines.push(Line::from(format!("{}: {}", time.date_naive(), note)));
+            }
+        }
+
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(block)
+                .wrap(Wrap { trim: false }),
+            area,
+        );
+    }
+}
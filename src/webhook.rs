@@ -0,0 +1,300 @@
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use url::Url;
+
+use crate::job::Job;
+
+/// Something that happened during a scrape run worth telling a chat about.
+#[derive(Clone, Debug)]
+pub enum NotifyEvent {
+    NewJob { score: i32, job: Job },
+    RecoveredJob { score: i32, job: Job },
+    SourceBroken { source: String, message: String },
+}
+
+/// A chat webhook to post events to.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    pub url: Url,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// Only post jobs with at least this score.
+    #[serde(default = "default_min_score")]
+    pub min_score: i32,
+    /// Only post jobs with a title matching this regex.
+    #[serde(default, with = "serde_regex")]
+    pub title_re: Option<Regex>,
+    /// Only post jobs from a company containing one of these names (case-insensitive).
+    #[serde(default)]
+    pub companies: Vec<String>,
+    /// Don't post when a job source looks broken.
+    #[serde(default)]
+    pub mute_broken_sources: bool,
+    /// The most events to post in one message.
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    /// The least time between messages, in milliseconds.
+    #[serde(default = "default_min_interval_ms")]
+    pub min_interval_ms: u64,
+    /// When the last message was posted, for rate limiting.
+    #[serde(skip)]
+    last_post: Option<Instant>,
+}
+
+fn default_min_score() -> i32 {
+    1
+}
+
+fn default_batch_size() -> usize {
+    10
+}
+
+fn default_min_interval_ms() -> u64 {
+    1000
+}
+
+/// The shape of the JSON payload to post.
+#[derive(Serialize, Deserialize, Default, Clone, Copy, Debug)]
+pub enum WebhookFormat {
+    /// `{"content": ".."}` with Markdown links.
+    Discord,
+    /// `{"text": ".."}` with Slack links. Also works with Matrix webhook bridges.
+    Slack,
+    /// `{"events": [..]}` with one object per event.
+    #[default]
+    Json,
+}
+
+impl WebhookConfig {
+    /// True if the event passes this webhook's filters.
+    pub fn wants(&self, event: &NotifyEvent) -> bool {
+        match event {
+            NotifyEvent::NewJob { score, job } | NotifyEvent::RecoveredJob { score, job } => {
+                *score >= self.min_score
+                    && self
+                        .title_re
+                        .as_ref()
+                        .is_none_or(|x| x.is_match(&job.title))
+                    && (self.companies.is_empty()
+                        || self
+                            .companies
+                            .iter()
+                            .any(|x| job.company.to_lowercase().contains(&x.to_lowercase())))
+            }
+            NotifyEvent::SourceBroken { .. } => !self.mute_broken_sources,
+        }
+    }
+
+    /// Posts the events that pass this webhook's filters, in batches.
+    pub async fn post(&mut self, client: &reqwest::Client, events: &[NotifyEvent]) {
+        let events = events.iter().filter(|x| self.wants(x)).collect::<Vec<_>>();
+        for batch in events.chunks(self.batch_size.max(1)) {
+            let payload = self.format.payload(batch);
+            if let Err(e) = self.post_payload(client, &payload).await {
+                log::error!("Couldn't post to webhook {}: {}", self.url, e);
+            }
+        }
+    }
+
+    /// Posts a payload, waiting for the rate limit and retrying once if rate limited.
+    async fn post_payload(
+        &mut self,
+        client: &reqwest::Client,
+        payload: &serde_json::Value,
+    ) -> reqwest::Result<()> {
+        for retry in [true, false] {
+            let min_interval = Duration::from_millis(self.min_interval_ms);
+            if let Some(elapsed) = self.last_post.map(|x| x.elapsed()) {
+                tokio::time::sleep(min_interval.saturating_sub(elapsed)).await;
+            }
+
+            let response = client.post(self.url.clone()).json(payload).send().await?;
+            self.last_post = Some(Instant::now());
+            if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS && retry {
+                let retry_after = response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|x| x.to_str().ok()?.parse::<f64>().ok())
+                    .unwrap_or(1.0);
+                log::warn!(
+                    "Rate limited by webhook {}, retrying in {}s",
+                    self.url,
+                    retry_after,
+                );
+                tokio::time::sleep(Duration::from_secs_f64(retry_after.clamp(0.0, 60.0))).await;
+                continue;
+            }
+            response.error_for_status()?;
+            break;
+        }
+        Ok(())
+    }
+}
+
+impl WebhookFormat {
+    pub fn payload(&self, events: &[&NotifyEvent]) -> serde_json::Value {
+        match self {
+            WebhookFormat::Discord => json!({
+                "content": events
+                    .iter()
+                    .map(|x| self.line(x))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            }),
+            WebhookFormat::Slack => json!({
+                "text": events
+                    .iter()
+                    .map(|x| self.line(x))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            }),
+            WebhookFormat::Json => json!({
+                "events": events
+                    .iter()
+                    .map(|x| match x {
+                        NotifyEvent::NewJob { score, job } => job_json("new_job", *score, job),
+                        NotifyEvent::RecoveredJob { score, job } => {
+                            job_json("recovered_job", *score, job)
+                        }
+                        NotifyEvent::SourceBroken { source, message } => json!({
+                            "type": "source_broken",
+                            "source": source,
+                            "message": message,
+                        }),
+                    })
+                    .collect::<Vec<_>>(),
+            }),
+        }
+    }
+
+    /// Describes an event in one line of chat.
+    fn line(&self, event: &NotifyEvent) -> String {
+        match event {
+            NotifyEvent::NewJob { score, job } => format!(
+                "New ({}): {}{}{} {}",
+                score,
+                self.escape("["),
+                self.escape(&job.company),
+                self.escape("]"),
+                self.link(&job.title, &job.url),
+            ),
+            NotifyEvent::RecoveredJob { score, job } => format!(
+                "Recovered ({}): {}{}{} {}",
+                score,
+                self.escape("["),
+                self.escape(&job.company),
+                self.escape("]"),
+                self.link(&job.title, &job.url),
+            ),
+            NotifyEvent::SourceBroken { source, message } => format!(
+                "Job source possibly broken: {}{}{} {}",
+                self.escape("["),
+                self.escape(source),
+                self.escape("]"),
+                self.escape(message),
+            ),
+        }
+    }
+
+    /// Escapes text so chat shows it as-is.
+    fn escape(&self, text: &str) -> String {
+        match self {
+            WebhookFormat::Discord => {
+                let mut escaped = String::new();
+                for c in text.chars() {
+                    if "\\*_~`|[]()<>".contains(c) {
+                        escaped.push('\\');
+                    }
+                    escaped.push(c);
+                }
+                escaped
+            }
+            WebhookFormat::Slack => text
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;"),
+            WebhookFormat::Json => text.to_string(),
+        }
+    }
+
+    /// Formats a link to a URL with some text.
+    fn link(&self, text: &str, url: &Url) -> String {
+        // Keep the URL from ending the link early.
+        let url = url
+            .as_str()
+            .replace('|', "%7C")
+            .replace('<', "%3C")
+            .replace('>', "%3E");
+        match self {
+            WebhookFormat::Discord => format!("[{}](<{}>)", self.escape(text), url),
+            WebhookFormat::Slack => format!("<{}|{}>", url, self.escape(text)),
+            WebhookFormat::Json => format!("{} ({})", text, url),
+        }
+    }
+}
+
+fn job_json(kind: &str, score: i32, job: &Job) -> serde_json::Value {
+    json!({
+        "type": kind,
+        "score": score,
+        "source": job.source,
+        "company": job.company,
+        "title": job.title,
+        "url": job.url,
+        "first_seen": job.first_seen,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload() {
+        let url = Url::parse("https://example.com/jobs/1").unwrap();
+        let job = Job::new("Source", "Company", url, "Gameplay Programmer");
+        let events = [
+            NotifyEvent::NewJob { score: 6, job },
+            NotifyEvent::SourceBroken {
+                source: "Source".to_string(),
+                message: "Found no jobs (had 10)".to_string(),
+            },
+        ];
+        let events = events.iter().collect::<Vec<_>>();
+
+        let slack = WebhookFormat::Slack.payload(&events);
+        assert_eq!(
+            slack["text"],
+            "New (6): [Company] <https://example.com/jobs/1|Gameplay Programmer>\n\
+             Job source possibly broken: [Source] Found no jobs (had 10)",
+        );
+        let json = WebhookFormat::Json.payload(&events);
+        assert_eq!(json["events"][0]["type"], "new_job");
+        assert_eq!(json["events"][1]["source"], "Source");
+    }
+
+    #[test]
+    fn payload_escaping() {
+        let url = Url::parse("https://example.com/jobs?a=1&b=|>").unwrap();
+        let job = Job::new("Source", "Tom & Jerry's <Games>", url, "C++ | C# [Remote]");
+        let events = [NotifyEvent::NewJob { score: 6, job }];
+        let events = events.iter().collect::<Vec<_>>();
+
+        let slack = WebhookFormat::Slack.payload(&events);
+        assert_eq!(
+            slack["text"],
+            "New (6): [Tom &amp; Jerry's &lt;Games&gt;] \
+             <https://example.com/jobs?a=1&b=%7C%3E|C++ | C# [Remote]>",
+        );
+        let discord = WebhookFormat::Discord.payload(&events);
+        assert_eq!(
+            discord["content"],
+            "New (6): \\[Tom & Jerry's \\<Games\\>\\] \
+             [C++ \\| C# \\[Remote\\]](<https://example.com/jobs?a=1&b=%7C%3E>)",
+        );
+    }
+}